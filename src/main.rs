@@ -1,27 +1,218 @@
 mod mlb_browser;
-use glutin_window::{GlutinWindow as Window};
+use chrono::NaiveDate;
+use glutin_window::GlutinWindow as Window;
 use image::{DynamicImage, ImageFormat};
+use mlb_browser::bindings::{Action, Bindings, Modifiers};
+use mlb_browser::cache::{Cache, DEFAULT_MAX_BYTES};
+use mlb_browser::config::{self, Args, Config};
+use mlb_browser::fixture_server::FixtureServer;
+use mlb_browser::gamepad::Gamepad;
+use mlb_browser::mlb_api::{MlbApi, MlbApiConfig};
+use mlb_browser::pointer::Pointer;
+use mlb_browser::repeat::KeyRepeat;
+use mlb_browser::window_mode::{WindowMode, WindowModeSwitcher};
 use mlb_browser::*;
 use opengl_graphics::{GlGraphics, OpenGL, Texture, TextureSettings};
 use piston::event_loop::{EventSettings, Events};
-use piston::input::{Button, Key, PressEvent, RenderEvent, UpdateEvent};
-use piston::window::WindowSettings;
+use piston::input::{
+    Button, FocusEvent, Key, MouseButton, MouseCursorEvent, MouseScrollEvent, PressEvent,
+    ReleaseEvent, RenderEvent, TouchEvent, UpdateEvent,
+};
+use piston::window::{Window as _, WindowSettings};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 const WIDTH: f64 = 1366.0;
 const HEIGHT: f64 = 768.0;
+const TITLE: &str = "DSS Exercise #1";
+// Where the browser opens when neither --date nor the config file say otherwise
+const START_DATE: (i32, u32, u32) = (2018, 6, 11);
+
+const USAGE: &str = "Usage: dss_interview1 [options]
+
+  --date YYYY-MM-DD       Open on this day (default 2018-06-11)
+  --today                 Open on today's date
+  --width <points>        Window width (default 1366)
+  --height <points>       Window height (default 768)
+  --fullscreen            Start fullscreen
+  --team <team>           Only show games of this team, by abbreviation, id or name
+  --config <path>         Read settings from this file instead of the default config.toml
+  --cache-dir <path>      Where schedules and images are cached
+  --cache-max-mb <n>      Size limit for the cache (default 200)
+  --clear-cache           Empty the cache and exit
+  --offline               Browse using only what is already cached
+  --fixtures <dir>        Serve recorded responses from a local server
+  --api-base <url>        Stats API root
+  --image-host <url>      Host to fetch recap images from
+  --image-concurrency <n> Images downloaded at the same time (default 4)
+";
+
+// Report a bad command line and exit
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    std::process::exit(1);
+}
 
 fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => usage_error(&e),
+    };
+    let has_flag = |name: &str| args.flag(name);
+    let arg_value = |name: &str| args.value(name);
+    if has_flag("--help") {
+        print!("{}", USAGE);
+        return;
+    }
+
+    // Settings from --config, or config.toml in the default location. Command line options
+    // override them.
+    let settings = match arg_value("--config") {
+        Some(path) => Config::load(Path::new(path)),
+        None => Config::load_default(),
+    };
+    let settings = match settings {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // --today wins over --date, which wins over the config file
+    let date_setting = if has_flag("--today") {
+        Some("today")
+    } else {
+        arg_value("--date").or(settings.date.as_deref())
+    };
+    let start_date = match date_setting {
+        Some(value) => match config::parse_date(value) {
+            Some(date) => date,
+            None => {
+                eprintln!("Invalid date \"{}\", expected YYYY-MM-DD or today", value);
+                std::process::exit(1);
+            }
+        },
+        None => NaiveDate::from_ymd_opt(START_DATE.0, START_DATE.1, START_DATE.2).unwrap(),
+    };
+    let positive = |points: &f64| points.is_finite() && *points > 0.0;
+    let size_setting =
+        |name: &str, configured: Option<f64>, default: f64| match args.parsed(name, positive) {
+            // Sizes from the config file were checked when it was loaded
+            Ok(points) => points.or(configured).unwrap_or(default),
+            Err(e) => usage_error(&e),
+        };
+    let width = size_setting("--width", settings.width, WIDTH);
+    let height = size_setting("--height", settings.height, HEIGHT);
+    let team = arg_value("--team")
+        .map(str::to_string)
+        .or_else(|| settings.team.clone());
+
+    // Megabytes that don't fit in a u64 once converted to bytes are as invalid as any other
+    let cache_max_mb = args.parsed("--cache-max-mb", |mb: &u64| {
+        mb.checked_mul(1024 * 1024).is_some()
+    });
+    let cache_max_bytes = match cache_max_mb {
+        Ok(mb) => mb.map_or(DEFAULT_MAX_BYTES, |mb| mb * 1024 * 1024),
+        Err(e) => usage_error(&e),
+    };
+    let mut cache = Cache::new(
+        arg_value("--cache-dir")
+            .map(PathBuf::from)
+            .or_else(|| settings.cache_dir.clone())
+            .unwrap_or_else(Cache::default_dir),
+        cache_max_bytes,
+    );
+    if has_flag("--clear-cache") {
+        match cache.clear() {
+            Ok(bytes) => println!(
+                "Cleared {} ({} KiB freed)",
+                cache.dir().display(),
+                bytes / 1024
+            ),
+            Err(e) => {
+                eprintln!("Unable to clear cache: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Serve schedules and images from the on-disk cache only
+    if has_flag("--offline") || settings.offline {
+        cache.set_offline(true);
+    }
+
+    // --fixtures <dir> replays recorded responses from a local server instead of the real API
+    let mut config = MlbApiConfig::default();
+    let _fixture_server = match arg_value("--fixtures") {
+        Some(dir) => match FixtureServer::start(PathBuf::from(dir), "127.0.0.1:0") {
+            Ok(server) => {
+                config = MlbApiConfig::local(&server.url());
+                Some(server)
+            }
+            Err(e) => {
+                eprintln!("Unable to start fixture server: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    if let Some(base_url) = arg_value("--api-base") {
+        config.base_url = base_url.trim_end_matches('/').to_string();
+    }
+    if let Some(image_host) = arg_value("--image-host") {
+        config.image_host = Some(image_host.to_string());
+    }
+    match args.parsed("--image-concurrency", |n: &usize| *n > 0) {
+        Ok(Some(n)) => config.image_concurrency = n,
+        Ok(None) => (),
+        Err(e) => usage_error(&e),
+    }
+    config.team = team.clone();
+
+    // Key bindings from the config file, on top of the defaults
+    let bindings = match Bindings::new(&settings.bindings) {
+        Ok(bindings) => bindings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let api = match MlbApi::new(config, cache) {
+        Ok(api) => api,
+        Err(e) => {
+            eprintln!("Unable to set up HTTP client: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     // Load OpenGL version
     let opengl = OpenGL::V3_3;
 
     // Create a Glutin window.
-    let mut window: Window = WindowSettings::new("DSS Exercise #1", [WIDTH, HEIGHT])
+    let title = match &team {
+        Some(team) => format!("{} - {}", TITLE, team),
+        None => TITLE.to_string(),
+    };
+    let mut window: Window = WindowSettings::new(title, [width, height])
         .graphics_api(opengl)
-        .exit_on_esc(true)
-        // Disable fullscreen for now -- appears to be broken on some versions of Windows
-        // .fullscreen(true)
+        // ESC closes the detail screen first, so exiting is handled below
+        .exit_on_esc(false)
+        // Fullscreen from the builder is broken on some versions of Windows, so the window
+        // always starts windowed and switches to the remembered mode once it exists
         .build()
         .unwrap();
+    let mut window_mode = WindowModeSwitcher::new();
+    let start_mode = if has_flag("--fullscreen") || settings.fullscreen {
+        Some(WindowMode::Fullscreen)
+    } else {
+        WindowMode::load()
+    };
+    if let Some(mode) = start_mode {
+        window_mode.set_mode(&mut window, mode);
+    }
 
     // Load the background image (binary resource)
     let img_bytes = include_bytes!("assets/1.jpg");
@@ -37,9 +228,21 @@ fn main() {
         GlGraphics::new(opengl),
         texture,
         (img.width() as f64, img.height() as f64),
-        (2018, 6, 11),
+        start_date,
+        api,
+        &bindings,
     );
 
+    // Modifiers held right now, for bindings such as Shift+PageUp
+    let mut modifiers = Modifiers::default();
+    // The navigation key being held down, repeating faster the longer it is held
+    let mut held_key: Option<Key> = None;
+    let mut key_repeat = KeyRepeat::new();
+    // Mouse and touchscreen input, turned into clicks and swipes
+    let mut pointer = Pointer::new();
+    // Connected gamepads, polled on each update
+    let mut gamepad = Gamepad::new();
+
     // Event loop for created window
     let mut events = Events::new(EventSettings::new());
     while let Some(e) = events.next(&mut window) {
@@ -51,25 +254,102 @@ fn main() {
         // Update based on dt
         if let Some(args) = e.update_args() {
             app.update(args);
+            for action in key_repeat
+                .update(args.dt)
+                .into_iter()
+                .chain(gamepad.update(args.dt))
+            {
+                app.perform(action);
+            }
         }
 
-        // Handle button events
-        if let Some(button) = e.press_args() {
-            match button {
-                Button::Keyboard(Key::Right) => {
-                    app.select_next();
-                }
-                Button::Keyboard(Key::Left) => {
-                    app.select_prev();
-                }
-                Button::Keyboard(Key::Up) => {
-                    app.increment_day();
-                }
-                Button::Keyboard(Key::Down) => {
-                    app.decrement_day();
+        // Track modifier state regardless of which screen is showing
+        match (e.press_args(), e.release_args()) {
+            (Some(Button::Keyboard(Key::LShift)), _) | (Some(Button::Keyboard(Key::RShift)), _) => {
+                modifiers.shift = true;
+            }
+            (_, Some(Button::Keyboard(Key::LShift))) | (_, Some(Button::Keyboard(Key::RShift))) => {
+                modifiers.shift = false;
+            }
+            (Some(Button::Keyboard(Key::LCtrl)), _) | (Some(Button::Keyboard(Key::RCtrl)), _) => {
+                modifiers.ctrl = true;
+            }
+            (_, Some(Button::Keyboard(Key::LCtrl))) | (_, Some(Button::Keyboard(Key::RCtrl))) => {
+                modifiers.ctrl = false;
+            }
+            (Some(Button::Keyboard(Key::LAlt)), _) | (Some(Button::Keyboard(Key::RAlt)), _) => {
+                modifiers.alt = true;
+            }
+            (_, Some(Button::Keyboard(Key::LAlt))) | (_, Some(Button::Keyboard(Key::RAlt))) => {
+                modifiers.alt = false;
+            }
+            _ => (),
+        }
+
+        // Stop repeating once the held key comes back up, or when the window loses focus and
+        // its release would go unseen
+        match (e.release_args(), e.focus_args()) {
+            (Some(Button::Keyboard(key)), _) if held_key == Some(key) => {
+                held_key = None;
+                key_repeat.release();
+            }
+            (_, Some(false)) => {
+                held_key = None;
+                key_repeat.release();
+                modifiers = Modifiers::default();
+            }
+            _ => (),
+        }
+
+        // Handle mouse and touch events
+        if let Some(position) = e.mouse_cursor_args() {
+            pointer.move_to(position);
+        }
+        let gesture = match (e.press_args(), e.release_args(), e.touch_args()) {
+            (Some(Button::Mouse(MouseButton::Left)), _, _) => {
+                pointer.mouse_press(Instant::now());
+                None
+            }
+            (_, Some(Button::Mouse(MouseButton::Left)), _) => pointer.mouse_release(Instant::now()),
+            (_, _, Some(touch)) => pointer.touch(&touch, Instant::now()),
+            _ => None,
+        };
+        if let Some(gesture) = gesture {
+            app.handle_gesture(gesture);
+        }
+        // The scroll wheel moves through the games
+        if let Some([_, dy]) = e.mouse_scroll_args() {
+            let steps = pointer.scroll(dy);
+            let action = if steps > 0 {
+                Action::NextGame
+            } else {
+                Action::PrevGame
+            };
+            for _ in 0..steps.abs() {
+                app.perform(action);
+            }
+        }
+
+        // Handle keyboard events
+        let action = match e.press_args() {
+            // The OS repeats held keys as well; those presses are ignored in favour of key_repeat
+            Some(Button::Keyboard(key)) if held_key == Some(key) => None,
+            Some(Button::Keyboard(key)) => {
+                let action = bindings.action(key, modifiers, app.showing_details());
+                if let Some(action) = action {
+                    key_repeat.press(action);
+                    held_key = key_repeat.held().map(|_| key);
                 }
-                _ => (),
+                action
             }
+            _ => None,
+        };
+        match action {
+            // Windowed -> borderless -> fullscreen, available on every screen
+            Some(Action::ToggleFullscreen) => window_mode.toggle(&mut window),
+            Some(Action::Quit) => window.set_should_close(true),
+            Some(action) => app.perform(action),
+            None => (),
         }
     }
 }
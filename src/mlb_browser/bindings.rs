@@ -0,0 +1,333 @@
+use super::config::ConfigError;
+use piston::input::Key;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::Deserialize as _;
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+// Everything the browser can be asked to do, whether from the keyboard, a gamepad or a config file
+// toml can't deserialize enum map keys directly, so actions go through their names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Action {
+    NextGame,
+    PrevGame,
+    NextDay,
+    PrevDay,
+    NextWeek,
+    PrevWeek,
+    NextMonth,
+    PrevMonth,
+    OpenDetails,
+    // Leave the detail screen
+    Back,
+    ToggleFullscreen,
+    Quit,
+}
+
+const ACTION_NAMES: &[(Action, &str)] = &[
+    (Action::NextGame, "next_game"),
+    (Action::PrevGame, "prev_game"),
+    (Action::NextDay, "next_day"),
+    (Action::PrevDay, "prev_day"),
+    (Action::NextWeek, "next_week"),
+    (Action::PrevWeek, "prev_week"),
+    (Action::NextMonth, "next_month"),
+    (Action::PrevMonth, "prev_month"),
+    (Action::OpenDetails, "open_details"),
+    (Action::Back, "back"),
+    (Action::ToggleFullscreen, "toggle_fullscreen"),
+    (Action::Quit, "quit"),
+];
+
+impl TryFrom<String> for Action {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        ACTION_NAMES
+            .iter()
+            .find(|(_, action_name)| *action_name == name)
+            .map(|&(action, _)| action)
+            .ok_or_else(|| format!("unknown action `{}`", name))
+    }
+}
+
+impl Action {
+    // The detail screen only reacts to Back and to switching window modes; everything else
+    // belongs to the carousel
+    fn available(self, showing_details: bool) -> bool {
+        match self {
+            Action::ToggleFullscreen => true,
+            Action::Back => showing_details,
+            _ => !showing_details,
+        }
+    }
+}
+
+const DEFAULT_BINDINGS: &[(Action, &str)] = &[
+    (Action::NextGame, "Right"),
+    (Action::PrevGame, "Left"),
+    (Action::NextDay, "Up"),
+    (Action::PrevDay, "Down"),
+    (Action::NextWeek, "PageUp"),
+    (Action::PrevWeek, "PageDown"),
+    (Action::NextMonth, "Shift+PageUp"),
+    (Action::PrevMonth, "Shift+PageDown"),
+    (Action::OpenDetails, "Return"),
+    (Action::OpenDetails, "NumPadEnter"),
+    (Action::Back, "Escape"),
+    (Action::Back, "Backspace"),
+    (Action::ToggleFullscreen, "F11"),
+    (Action::ToggleFullscreen, "Alt+Return"),
+    (Action::ToggleFullscreen, "Alt+NumPadEnter"),
+    (Action::Quit, "Escape"),
+];
+
+// The help line, as (actions, what they do). Each action shows its first key.
+const HELP: &[(&[Action], &str)] = &[
+    (&[Action::PrevGame, Action::NextGame], "to navigate"),
+    (&[Action::NextDay, Action::PrevDay], "to change dates"),
+    (&[Action::NextWeek, Action::PrevWeek], "for weeks"),
+    (&[Action::NextMonth, Action::PrevMonth], "for months"),
+    (&[Action::OpenDetails], "for details"),
+    (&[Action::ToggleFullscreen], "for fullscreen"),
+    (&[Action::Quit], "to exit"),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    fn count(self) -> usize {
+        self.shift as usize + self.ctrl as usize + self.alt as usize
+    }
+
+    // Whether every modifier in `self` is held
+    fn held_in(self, held: Modifiers) -> bool {
+        (!self.shift || held.shift) && (!self.ctrl || held.ctrl) && (!self.alt || held.alt)
+    }
+}
+
+// A key plus the modifiers that must be held with it, written like "Shift+PageUp". Key names
+// are Piston's, e.g. "Right", "Return", "F11" or "D".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyBinding {
+    key: Key,
+    modifiers: Modifiers,
+}
+
+impl KeyBinding {
+    pub fn parse(binding: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = binding.split('+').map(str::trim).collect();
+        let key = match parts.pop()? {
+            // Friendlier names for the keys that are labelled differently
+            "Enter" => Key::Return,
+            "Esc" => Key::Escape,
+            name => Key::deserialize(StrDeserializer::<ValueError>::new(name)).ok()?,
+        };
+        let mut modifiers = Modifiers::default();
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "shift" => modifiers.shift = true,
+                "ctrl" => modifiers.ctrl = true,
+                "alt" => modifiers.alt = true,
+                _ => return None,
+            }
+        }
+        Some(KeyBinding { key, modifiers })
+    }
+
+    fn label(&self) -> String {
+        let key = match self.key {
+            Key::Right => "→".to_string(),
+            Key::Left => "←".to_string(),
+            Key::Up => "↑".to_string(),
+            Key::Down => "↓".to_string(),
+            Key::PageUp => "PgUp".to_string(),
+            Key::PageDown => "PgDn".to_string(),
+            Key::Return | Key::NumPadEnter => "Enter".to_string(),
+            Key::Escape => "ESC".to_string(),
+            key => format!("{:?}", key),
+        };
+        let mut label = String::new();
+        for (held, name) in [
+            (self.modifiers.ctrl, "Ctrl+"),
+            (self.modifiers.alt, "Alt+"),
+            (self.modifiers.shift, "Shift+"),
+        ] {
+            if held {
+                label.push_str(name);
+            }
+        }
+        label + &key
+    }
+}
+
+// Which keys trigger which actions
+pub struct Bindings {
+    bindings: Vec<(Action, KeyBinding)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings::new(&BTreeMap::new()).unwrap()
+    }
+}
+
+impl Bindings {
+    // The defaults, with the keys of any action listed in `overrides` replaced
+    pub fn new(overrides: &BTreeMap<Action, Vec<String>>) -> Result<Self, ConfigError> {
+        let defaults = DEFAULT_BINDINGS
+            .iter()
+            .filter(|(action, _)| !overrides.contains_key(action))
+            .map(|&(action, binding)| (action, binding.to_string()));
+        let configured = overrides
+            .iter()
+            .flat_map(|(&action, keys)| keys.iter().map(move |key| (action, key.clone())));
+        let bindings = defaults
+            .chain(configured)
+            .map(|(action, binding)| match KeyBinding::parse(&binding) {
+                Some(key) => Ok((action, key)),
+                None => Err(ConfigError::Binding { action, binding }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Bindings { bindings })
+    }
+
+    // The action for a key press on the current screen. When several bindings match, the one
+    // asking for the most modifiers wins, so Shift+PageUp isn't also a plain PageUp.
+    pub fn action(&self, key: Key, held: Modifiers, showing_details: bool) -> Option<Action> {
+        self.bindings
+            .iter()
+            .filter(|(action, binding)| {
+                binding.key == key
+                    && binding.modifiers.held_in(held)
+                    && action.available(showing_details)
+            })
+            .max_by_key(|(_, binding)| binding.modifiers.count())
+            .map(|&(action, _)| action)
+    }
+
+    fn first(&self, action: Action) -> Option<&KeyBinding> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, binding)| binding)
+    }
+
+    // One line describing the active keys, e.g. "Use ← → to navigate, ..., ESC to exit"
+    pub fn help(&self) -> String {
+        let mut parts = Vec::new();
+        for &(actions, purpose) in HELP {
+            let keys: Vec<&KeyBinding> = actions.iter().filter_map(|&a| self.first(a)).collect();
+            if keys.is_empty() {
+                continue;
+            }
+            let labels: Vec<String> = keys.iter().map(|key| key.label()).collect();
+            parts.push(format!("{} {}", labels.join(" "), purpose));
+        }
+        format!("Use {}", parts.join(", "))
+    }
+
+    // The footer of the detail screen, e.g. "Press ESC or Backspace to return". Empty when
+    // nothing is bound to Back.
+    pub fn back_help(&self) -> String {
+        let labels: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(action, _)| *action == Action::Back)
+            .map(|(_, binding)| binding.label())
+            .collect();
+        if labels.is_empty() {
+            return String::new();
+        }
+        format!("Press {} to return", labels.join(" or "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+    const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..NONE
+    };
+    const ALT: Modifiers = Modifiers { alt: true, ..NONE };
+
+    #[test]
+    fn defaults_depend_on_modifiers_and_screen() {
+        let bindings = Bindings::default();
+        let action = |key, held, details| bindings.action(key, held, details);
+        assert_eq!(action(Key::PageUp, NONE, false), Some(Action::NextWeek));
+        assert_eq!(action(Key::PageUp, SHIFT, false), Some(Action::NextMonth));
+        // Extra modifiers don't get in the way of plain bindings
+        assert_eq!(action(Key::Right, SHIFT, false), Some(Action::NextGame));
+        assert_eq!(action(Key::Return, NONE, false), Some(Action::OpenDetails));
+        assert_eq!(
+            action(Key::Return, ALT, true),
+            Some(Action::ToggleFullscreen)
+        );
+        assert_eq!(action(Key::Escape, NONE, false), Some(Action::Quit));
+        assert_eq!(action(Key::Escape, NONE, true), Some(Action::Back));
+        assert_eq!(action(Key::Right, NONE, true), None);
+    }
+
+    #[test]
+    fn configured_keys_replace_the_defaults() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert(
+            Action::NextGame,
+            vec!["D".to_string(), "ctrl+Right".to_string()],
+        );
+        overrides.insert(Action::Quit, Vec::new());
+        let bindings = Bindings::new(&overrides).unwrap();
+        assert_eq!(bindings.action(Key::D, NONE, false), Some(Action::NextGame));
+        assert_eq!(bindings.action(Key::Right, NONE, false), None);
+        let ctrl = Modifiers { ctrl: true, ..NONE };
+        assert_eq!(
+            bindings.action(Key::Right, ctrl, false),
+            Some(Action::NextGame)
+        );
+        assert_eq!(bindings.action(Key::Escape, NONE, false), None);
+
+        overrides.insert(Action::Back, vec!["Hyper+Q".to_string()]);
+        assert!(Bindings::new(&overrides).is_err());
+        assert_eq!(KeyBinding::parse("Shift+Nope"), None);
+    }
+
+    #[test]
+    fn help_lists_the_active_keys() {
+        assert_eq!(
+            Bindings::default().help(),
+            "Use ← → to navigate, ↑ ↓ to change dates, PgUp PgDn for weeks, \
+             Shift+PgUp Shift+PgDn for months, Enter for details, F11 for fullscreen, ESC to exit"
+        );
+        let mut overrides = BTreeMap::new();
+        overrides.insert(Action::PrevGame, vec!["A".to_string()]);
+        overrides.insert(Action::ToggleFullscreen, Vec::new());
+        let help = Bindings::new(&overrides).unwrap().help();
+        assert!(help.starts_with("Use A → to navigate"));
+        assert!(!help.contains("fullscreen"));
+
+        assert_eq!(
+            Bindings::default().back_help(),
+            "Press ESC or Backspace to return"
+        );
+        overrides.insert(Action::Back, vec!["Q".to_string()]);
+        let bindings = Bindings::new(&overrides).unwrap();
+        assert_eq!(bindings.back_help(), "Press Q to return");
+        overrides.insert(Action::Back, Vec::new());
+        assert_eq!(Bindings::new(&overrides).unwrap().back_help(), "");
+    }
+}
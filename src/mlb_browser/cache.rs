@@ -0,0 +1,281 @@
+use super::mlb_api::MlbError;
+use std::fs::{self, create_dir_all, remove_dir_all, File};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// Default byte budget for everything stored under the cache directory
+pub const DEFAULT_MAX_BYTES: u64 = 200 * 1024 * 1024;
+
+// The subdirectories the cache owns. The cache directory itself is chosen by the user and may
+// hold other files, so only these are ever scanned, evicted from or cleared.
+const NAMESPACES: &[&str] = &["http", "images"];
+
+// Marks files that are still being written; see Cache::write
+const TEMP_MARKER: &str = ".tmp-";
+// Temporary files older than this were left behind by a crashed instance
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60 * 60);
+// Keeps temporary file names unique between threads of one process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+// Cache::size before the directory has been scanned for the first time
+const UNKNOWN_SIZE: u64 = u64::MAX;
+
+// On-disk store shared by the schedule response cache and the recap images.
+// Entries are plain files; their modification time doubles as the last-used time so the
+// least recently used ones can be evicted once the directory grows past its byte budget.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    max_bytes: u64,
+    // When set, schedules and images are only ever served from disk
+    offline: bool,
+    // Bytes in the cache as of the last scan plus everything written since, shared by every
+    // clone. Overwritten entries are counted twice, so this errs on the high side until the
+    // next scan corrects it.
+    size: Arc<AtomicU64>,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Cache {
+            dir,
+            max_bytes,
+            offline: false,
+            size: Arc::new(AtomicU64::new(UNKNOWN_SIZE)),
+        }
+    }
+
+    // $XDG_CACHE_HOME/dss_interview1 (or the platform equivalent), falling back to ./cache
+    pub fn default_dir() -> PathBuf {
+        match dirs::cache_dir() {
+            Some(dir) => dir.join("dss_interview1"),
+            None => PathBuf::from("./cache"),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    // Where the entry `key` lives within one of NAMESPACES
+    pub fn path(&self, namespace: &str, key: &str) -> PathBuf {
+        debug_assert!(NAMESPACES.contains(&namespace));
+        self.dir.join(namespace).join(key)
+    }
+
+    // Read an entry, marking it as recently used
+    pub fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        let bytes = fs::read(path).ok()?;
+        touch(path);
+        Some(bytes)
+    }
+
+    // Entries are written to a temporary file next to their final name and then renamed into
+    // place, so readers (including other running instances) see either the old entry, the new
+    // one or none at all, never a partial file
+    pub fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), MlbError> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).map_err(|e| MlbError::cache_io(parent, e))?;
+        }
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(format!(
+            "{}{}-{}",
+            TEMP_MARKER,
+            process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = path.with_file_name(temp_name);
+        let written = fs::write(&temp_path, bytes).and_then(|_| fs::rename(&temp_path, path));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(MlbError::cache_io(path, e));
+        }
+        // Scanning the directory is only worth it once the cache may have outgrown its budget
+        let len = bytes.len() as u64;
+        let size = self
+            .size
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |size| {
+                Some(size.saturating_add(len))
+            })
+            .unwrap_or_else(|size| size);
+        if size.saturating_add(len) > self.max_bytes {
+            self.evict();
+        }
+        Ok(())
+    }
+
+    // Drop a single entry, e.g. one that turned out to be corrupt
+    pub fn remove(&self, path: &Path) {
+        let _ = fs::remove_file(path);
+    }
+
+    // Delete least recently used entries until the cache fits in its budget. This stats every
+    // entry, so writes only call it once the running size estimate goes over budget.
+    pub fn evict(&self) {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(_, _, used)| *used);
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            // Another instance may have evicted it already
+            let _ = fs::remove_file(&path);
+            total -= len;
+        }
+        self.size.store(total, Ordering::Relaxed);
+    }
+
+    // Remove every entry, returning the number of bytes freed. Anything else in the cache
+    // directory is left alone.
+    pub fn clear(&self) -> Result<u64, MlbError> {
+        let freed = self.entries().iter().map(|(_, len, _)| len).sum();
+        for namespace in NAMESPACES {
+            let dir = self.dir.join(namespace);
+            if dir.exists() {
+                remove_dir_all(&dir).map_err(|e| MlbError::cache_io(&dir, e))?;
+            }
+        }
+        self.size.store(0, Ordering::Relaxed);
+        Ok(freed)
+    }
+
+    // (path, size, last used) for every entry in every namespace
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let mut entries = Vec::new();
+        for namespace in NAMESPACES {
+            collect_files(&self.dir.join(namespace), &mut entries);
+        }
+        entries
+    }
+}
+
+// Stable across builds and platforms, unlike std's DefaultHasher
+pub fn hash_key(s: &str) -> String {
+    let hash = s.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn touch(path: &Path) {
+    if let Ok(file) = File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+// Recursively gather (path, size, last used) for every file below `dir`
+fn collect_files(dir: &Path, entries: &mut Vec<(PathBuf, u64, SystemTime)>) {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(_) => return,
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        if metadata.is_dir() {
+            collect_files(&path, entries);
+        } else {
+            let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            // Leave writes in progress alone, but count abandoned ones so they get evicted
+            let is_temp = entry.file_name().to_string_lossy().contains(TEMP_MARKER);
+            let age = used.elapsed().unwrap_or_default();
+            if !is_temp || age > STALE_TEMP_AGE {
+                entries.push((path, metadata.len(), used));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_cache;
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn concurrent_writers_never_expose_partial_entries() {
+        let (_dir, cache) = test_cache("cache-concurrent");
+        let path = cache.path("images", "shared");
+        let writers: Vec<_> = [b'a', b'b']
+            .iter()
+            .map(|&fill| {
+                let (cache, path) = (cache.clone(), path.clone());
+                thread::spawn(move || {
+                    for _ in 0..50 {
+                        cache.write(&path, &vec![fill; 256 * 1024]).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for _ in 0..200 {
+            if let Some(bytes) = cache.read(&path) {
+                assert_eq!(bytes.len(), 256 * 1024);
+                assert!(bytes.iter().all(|&b| b == bytes[0]));
+            }
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        // Only the entry itself is left, no temporary files
+        let names: Vec<_> = fs::read_dir(cache.path("images", ""))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["shared"]);
+    }
+
+    #[test]
+    fn leaves_files_it_does_not_own_alone() {
+        let (_dir, mut cache) = test_cache("cache-foreign");
+        cache.max_bytes = 10;
+        create_dir_all(cache.dir().join("notes")).unwrap();
+        let foreign = [
+            cache.dir().join("notes").join("todo.txt"),
+            cache.dir().join("big.bin"),
+        ];
+        for path in &foreign {
+            fs::write(path, vec![0; 1024]).unwrap();
+        }
+        cache
+            .write(&cache.path("images", "tile"), &[1; 100])
+            .unwrap();
+        // The oversized entry is evicted, but files outside the namespaces don't count
+        assert!(cache.read(&cache.path("images", "tile")).is_none());
+        cache
+            .write(&cache.path("http", "day.json"), &[1; 5])
+            .unwrap();
+        assert_eq!(cache.clear().unwrap(), 5);
+        assert!(foreign.iter().all(|path| path.exists()));
+        assert!(!cache.path("http", "").exists());
+    }
+
+    #[test]
+    fn only_scans_once_the_estimate_is_over_budget() {
+        let (_dir, mut cache) = test_cache("cache-estimate");
+        cache.max_bytes = 1000;
+        let total = |cache: &Cache| -> u64 { cache.entries().iter().map(|(_, len, _)| len).sum() };
+        cache.write(&cache.path("images", "a"), &[1; 100]).unwrap();
+        // Written behind the cache's back, e.g. by another instance, so it isn't in the estimate
+        fs::write(cache.path("images", "foreign"), vec![0; 950]).unwrap();
+        cache.write(&cache.path("images", "b"), &[1; 100]).unwrap();
+        assert_eq!(total(&cache), 1150);
+        // Going over the estimated budget rescans and evicts down to the real budget
+        cache.write(&cache.path("images", "c"), &[1; 900]).unwrap();
+        assert!(total(&cache) <= 1000);
+        assert!(cache.read(&cache.path("images", "c")).is_some());
+    }
+}
@@ -0,0 +1,309 @@
+use super::bindings::Action;
+use chrono::{Local, NaiveDate};
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Settings read from config.toml. Everything is optional and falls back to the built-in default,
+// and the command line options of the same name take precedence.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // Day to open on, "YYYY-MM-DD" or "today"
+    pub date: Option<String>,
+    // Window size in points
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    // Start fullscreen instead of in the window mode used last time
+    #[serde(default)]
+    pub fullscreen: bool,
+    // Only list games this team plays in, by abbreviation, id or part of its name
+    pub team: Option<String>,
+    pub cache_dir: Option<PathBuf>,
+    // Browse using only what is already cached
+    #[serde(default)]
+    pub offline: bool,
+    // Keys for each action, e.g. `next_game = ["Right", "Shift+D"]`. Listing an action replaces
+    // all of its default keys, and an empty list unbinds it.
+    #[serde(default)]
+    pub bindings: BTreeMap<Action, Vec<String>>,
+}
+
+// Command line options that take a value, and those that don't
+const VALUE_OPTIONS: &[&str] = &[
+    "--date",
+    "--width",
+    "--height",
+    "--team",
+    "--config",
+    "--cache-dir",
+    "--cache-max-mb",
+    "--fixtures",
+    "--api-base",
+    "--image-host",
+    "--image-concurrency",
+];
+const FLAGS: &[&str] = &[
+    "--help",
+    "--today",
+    "--fullscreen",
+    "--clear-cache",
+    "--offline",
+];
+
+// The command line, checked against the options above. Values follow their option as a separate
+// argument; when an option is repeated the last value wins.
+#[derive(Debug, Default)]
+pub struct Args {
+    flags: Vec<String>,
+    values: BTreeMap<String, String>,
+}
+
+impl Args {
+    // `args` excludes the program name. Errors describe the first offending argument.
+    pub fn parse<I>(args: I) -> Result<Args, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if FLAGS.contains(&arg.as_str()) {
+                parsed.flags.push(arg);
+            } else if VALUE_OPTIONS.contains(&arg.as_str()) {
+                // `--team --offline` is a forgotten value, not a team called "--offline"
+                match args.next() {
+                    Some(value) if !value.starts_with("--") => {
+                        parsed.values.insert(arg, value);
+                    }
+                    _ => return Err(format!("Missing value for {}", arg)),
+                }
+            } else {
+                return Err(format!("Unknown option \"{}\"", arg));
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    // The value of `name` as a `T`, if given. Values that don't parse or that `valid` rejects
+    // are an error rather than silently replaced by the default.
+    pub fn parsed<T, F>(&self, name: &str, valid: F) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        F: Fn(&T) -> bool,
+    {
+        match self.value(name) {
+            Some(value) => match value.parse().ok().filter(|parsed| valid(parsed)) {
+                Some(parsed) => Ok(Some(parsed)),
+                None => Err(format!("Invalid value \"{}\" for {}", value, name)),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    // A setting with a value it can't have, e.g. a zero width
+    Value {
+        path: PathBuf,
+        setting: &'static str,
+        value: String,
+    },
+    // A key binding that doesn't name a key
+    Binding {
+        action: Action,
+        binding: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "Unable to read {}: {}", path.display(), source)
+            }
+            ConfigError::Parse { path, source } => {
+                write!(f, "Invalid config in {}: {}", path.display(), source)
+            }
+            ConfigError::Value {
+                path,
+                setting,
+                value,
+            } => write!(
+                f,
+                "Invalid {} {} in {}, expected a positive number",
+                setting,
+                value,
+                path.display()
+            ),
+            ConfigError::Binding { action, binding } => {
+                write!(f, "Unknown key \"{}\" bound to {:?}", binding, action)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    // `$XDG_CONFIG_HOME/dss_interview1/config.toml` or the platform equivalent
+    pub fn default_path() -> Option<PathBuf> {
+        Some(
+            dirs::config_dir()?
+                .join("dss_interview1")
+                .join("config.toml"),
+        )
+    }
+
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config: Config = toml::from_str(&text).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        config.check(path)
+    }
+
+    // Reject values that parse but make no sense, rather than quietly using the default
+    fn check(self, path: &Path) -> Result<Config, ConfigError> {
+        for (setting, value) in [("width", self.width), ("height", self.height)] {
+            if let Some(points) = value.filter(|points| !points.is_finite() || *points <= 0.0) {
+                return Err(ConfigError::Value {
+                    path: path.to_path_buf(),
+                    setting,
+                    value: points.to_string(),
+                });
+            }
+        }
+        Ok(self)
+    }
+
+    // The config in the default location, if there is one
+    pub fn load_default() -> Result<Config, ConfigError> {
+        match Config::default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+}
+
+// A start date as given to --date or in the config file
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    match value.trim() {
+        "today" => Some(Local::now().date_naive()),
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bindings_and_rejects_unknown_settings() {
+        let config: Config = toml::from_str(
+            r#"
+            [bindings]
+            next_game = ["Right", "D"]
+            quit = []
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.bindings[&Action::NextGame], vec!["Right", "D"]);
+        assert!(config.bindings[&Action::Quit].is_empty());
+        assert!(!config.fullscreen);
+
+        let config: Config = toml::from_str(
+            r#"
+            date = "2018-07-04"
+            width = 1920.0
+            fullscreen = true
+            team = "BOS"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.date.as_deref(), Some("2018-07-04"));
+        assert_eq!(config.width, Some(1920.0));
+        assert_eq!(config.height, None);
+        assert!(config.fullscreen);
+        assert_eq!(config.team.as_deref(), Some("BOS"));
+
+        assert!(toml::from_str::<Config>("[bindings]\nfly = [\"F\"]").is_err());
+
+        let path = Path::new("config.toml");
+        for size in &["width = 0.0", "height = -768.0", "width = nan"] {
+            let config: Config = toml::from_str(size).unwrap();
+            assert!(matches!(config.check(path), Err(ConfigError::Value { .. })));
+        }
+        let config: Config = toml::from_str("width = 800.0").unwrap();
+        assert!(config.check(path).is_ok());
+        assert!(toml::from_str::<Config>("colour = \"blue\"").is_err());
+    }
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_command_line_options() {
+        let parsed = args(&["--team", "BOS", "--offline", "--width", "800"]).unwrap();
+        assert_eq!(parsed.value("--team"), Some("BOS"));
+        assert!(parsed.flag("--offline"));
+        assert!(!parsed.flag("--today"));
+        assert_eq!(
+            parsed.parsed("--width", |w: &f64| *w > 0.0),
+            Ok(Some(800.0))
+        );
+        assert_eq!(parsed.parsed("--height", |h: &f64| *h > 0.0), Ok(None));
+        let bad = args(&["--width", "-5"]).unwrap();
+        assert!(bad.parsed("--width", |w: &f64| *w > 0.0).is_err());
+        let bad = args(&["--cache-max-mb", "lots"]).unwrap();
+        assert!(bad.parsed::<u64, _>("--cache-max-mb", |_| true).is_err());
+
+        assert_eq!(
+            args(&["--date=2018-06-11"]).unwrap_err(),
+            "Unknown option \"--date=2018-06-11\""
+        );
+        assert_eq!(
+            args(&["--team", "--offline"]).unwrap_err(),
+            "Missing value for --team"
+        );
+        assert!(args(&["--width"]).is_err());
+        assert!(args(&["2018-06-11"]).is_err());
+    }
+
+    #[test]
+    fn parses_start_dates() {
+        assert_eq!(
+            parse_date("2018-06-11"),
+            NaiveDate::from_ymd_opt(2018, 6, 11)
+        );
+        assert!(parse_date("today").is_some());
+        assert_eq!(parse_date("2018-13-01"), None);
+        assert_eq!(parse_date("June 11"), None);
+    }
+}
@@ -0,0 +1,353 @@
+use super::mlb_api::Game;
+use super::MenuItem;
+use chrono::NaiveDate;
+use graphics::math::Matrix2d;
+use graphics::{text, Transformed};
+use image::RgbaImage;
+use opengl_graphics::{GlGraphics, GlyphCache, Texture, TextureSettings};
+
+const LABEL: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+// How much the selected tile grows once its transition has finished
+const SELECTED_SCALE: f64 = 1.5;
+
+// Where a day is in getting its games on screen. Every row starts out Loading and moves to
+// one of the other states once, when its schedule request finishes.
+pub enum DayState {
+    Loading,
+    // Never empty; a day without games is Empty
+    Loaded(Vec<MenuItem>),
+    Empty,
+    Failed(String),
+}
+
+// One horizontal carousel of games for a single date, with its own selection
+pub struct DayRow {
+    pub date: NaiveDate,
+    pub state: DayState,
+    // Only ever Some while Loaded, and then always a valid index
+    pub selected_idx: Option<usize>,
+    // Scroll position, in tiles, that the current scroll started from. Moving again mid-scroll
+    // starts from wherever the row has got to, so quick presses glide instead of snapping.
+    scroll_from: f64,
+    trans_time: f64,
+}
+
+impl DayRow {
+    pub fn new(date: NaiveDate) -> Self {
+        DayRow {
+            date,
+            state: DayState::Loading,
+            selected_idx: None,
+            scroll_from: 0.0,
+            trans_time: 0.0,
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self.state, DayState::Loading)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        matches!(self.state, DayState::Loaded(_))
+    }
+
+    pub fn set_games(&mut self, games: Vec<Game>) {
+        let items: Vec<MenuItem> = games
+            .into_iter()
+            .map(|g| MenuItem::new(g, 200.0, 200.0 * 9.0 / 16.0))
+            .collect();
+        if items.is_empty() {
+            self.state = DayState::Empty;
+            self.selected_idx = None;
+        } else {
+            self.state = DayState::Loaded(items);
+            self.selected_idx = Some(0);
+        }
+        self.scroll_from = 0.0;
+    }
+
+    pub fn set_failed(&mut self, error: String) {
+        self.state = DayState::Failed(error);
+        self.selected_idx = None;
+        self.scroll_from = 0.0;
+    }
+
+    // The tiles of a loaded day; empty in every other state
+    fn items(&self) -> &[MenuItem] {
+        match &self.state {
+            DayState::Loaded(items) => items,
+            _ => &[],
+        }
+    }
+
+    fn items_mut(&mut self) -> &mut [MenuItem] {
+        match &mut self.state {
+            DayState::Loaded(items) => items,
+            _ => &mut [],
+        }
+    }
+
+    // Attach a freshly fetched recap image to the matching game, if it is in this row. A smaller
+    // cut finishing after a larger one never replaces it.
+    pub fn set_image(&mut self, game_pk: u32, img: &RgbaImage) {
+        if let Some(item) = self
+            .items_mut()
+            .iter_mut()
+            .find(|item| item.game.gamePk == game_pk && item.img_width < img.width())
+        {
+            item.img_tex = Some(Texture::from_image(img, &TextureSettings::new()));
+            item.img_width = img.width();
+        }
+    }
+
+    // Fall back to the placeholder, unless a smaller cut of the image is already showing
+    pub fn image_failed(&mut self, game_pk: u32) {
+        for item in self.items_mut() {
+            if item.game.gamePk == game_pk && item.img_tex.is_none() {
+                item.img_failed = true;
+            }
+        }
+    }
+
+    // (loaded, expected) recap images; games without a photo aren't expected to have one
+    pub fn image_progress(&self) -> (usize, usize) {
+        let expected = self
+            .items()
+            .iter()
+            .filter(|item| item.game.recap_image(0).is_some());
+        let loaded = expected
+            .clone()
+            .filter(|item| item.img_tex.is_some() || item.img_failed)
+            .count();
+        (loaded, expected.count())
+    }
+
+    // Games ordered by distance from the selection, nearest first
+    pub fn focus_order(&self) -> Vec<u32> {
+        let selected_idx = self.selected_idx.unwrap_or(0);
+        let mut items: Vec<(usize, u32)> = self
+            .items()
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let distance = (idx as isize - selected_idx as isize).unsigned_abs();
+                (distance, item.game.gamePk)
+            })
+            .collect();
+        items.sort_by_key(|(distance, _)| *distance);
+        items.into_iter().map(|(_, game_pk)| game_pk).collect()
+    }
+
+    // (game, URL) for every tile that needs a larger image than it has asked for so far, given
+    // the window's pixels per point. Only the current row has a (scaled up) selected tile.
+    pub fn wanted_images(&mut self, is_current: bool, dpi_scale: f64) -> Vec<(u32, String)> {
+        let selected_idx = self.selected_idx;
+        let mut wanted = Vec::new();
+        for (idx, item) in self.items_mut().iter_mut().enumerate() {
+            let scale = if is_current && selected_idx == Some(idx) {
+                SELECTED_SCALE
+            } else {
+                1.0
+            };
+            let min_width = (item.width * scale * dpi_scale).ceil() as u32;
+            if let Some(source) = item.game.recap_image(min_width) {
+                if source.width > item.requested_width {
+                    item.requested_width = source.width;
+                    wanted.push((item.game.gamePk, source.url));
+                }
+            }
+        }
+        wanted
+    }
+
+    pub fn selected_game(&self) -> Option<&Game> {
+        let selected = self.selected_idx?;
+        self.items().get(selected).map(|item| &item.game)
+    }
+
+    // Move the selection straight to a tile, e.g. one that was clicked
+    pub fn select(&mut self, idx: usize) {
+        if self.selected_idx.is_some() && self.selected_idx != Some(idx) && idx < self.items().len()
+        {
+            self.scroll_to(idx);
+        }
+    }
+
+    // Selection wraps around at either end; days without games have nothing to move through
+    pub fn select_next(&mut self) {
+        let len = self.items().len();
+        if let Some(selected) = self.selected_idx {
+            self.scroll_to((selected + 1) % len);
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        let len = self.items().len();
+        if let Some(selected) = self.selected_idx {
+            self.scroll_to((selected + len - 1) % len);
+        }
+    }
+
+    fn scroll_to(&mut self, idx: usize) {
+        self.scroll_from = self.scroll_offset();
+        self.selected_idx = Some(idx);
+        self.trans_time = 0.0;
+    }
+
+    // Index of the tile at the centre of the row, fractional while scrolling. The scroll eases
+    // out so it decelerates into place.
+    fn scroll_offset(&self) -> f64 {
+        let target = self.selected_idx.unwrap_or(0) as f64;
+        let eased = 1.0 - (1.0 - self.trans_time).powi(2);
+        self.scroll_from + (target - self.scroll_from) * eased
+    }
+
+    // Horizontal position of a tile's centre relative to the row's, part way through the scroll
+    fn tile_x(&self, idx: usize, item: &MenuItem) -> f64 {
+        (idx as f64 - self.scroll_offset()) * item.width * 1.3
+    }
+
+    fn animated_scale(&self) -> f64 {
+        1.0 + (self.trans_time * (SELECTED_SCALE - 1.0))
+    }
+
+    // The tile under `position`, given relative to the row's centre as passed to render
+    pub fn tile_at(&self, is_current: bool, position: [f64; 2]) -> Option<usize> {
+        self.items().iter().enumerate().position(|(idx, item)| {
+            let scale = if is_current && self.selected_idx == Some(idx) {
+                self.animated_scale()
+            } else {
+                1.0
+            };
+            let (half_width, half_height) = (item.width * scale / 2.0, item.height * scale / 2.0);
+            (position[0] - self.tile_x(idx, item)).abs() <= half_width
+                && position[1].abs() <= half_height
+        })
+    }
+
+    // Draw the row centred on `transform`. Only the current row highlights its selection;
+    // the rows for the neighbouring days are dimmed and labelled with their date.
+    pub fn render(
+        &self,
+        is_current: bool,
+        transform: Matrix2d,
+        left: Matrix2d,
+        glyph_cache: &mut GlyphCache,
+        gl: &mut GlGraphics,
+    ) {
+        let selected_idx = self.selected_idx.unwrap_or(0);
+        let animated_scale = self.animated_scale();
+
+        if !is_current {
+            let date_str = self.date.format("%Y-%m-%d").to_string();
+            text(LABEL, 18, &date_str, glyph_cache, left, gl).unwrap();
+        }
+
+        // For each item in our items list, render it. The other states are drawn by MlbApp.
+        self.items().iter().enumerate().for_each(|(idx, item)| {
+            // Compute the transform for this item
+            let transform = transform.trans(self.tile_x(idx, item), 0.0);
+            // Render it
+            item.render(
+                is_current && selected_idx == idx,
+                !is_current,
+                transform,
+                animated_scale,
+                glyph_cache,
+                gl,
+            );
+        });
+    }
+
+    pub fn update(&mut self, dt: f64) {
+        // Until our transition time scalar reaches 1, increment
+        if self.trans_time < 1.0 {
+            // This means 100%  in 100 ms
+            self.trans_time += dt * 10.0;
+            if self.trans_time > 1.0 {
+                self.trans_time = 1.0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mlb_api::Schedule;
+    use super::*;
+
+    fn games() -> Vec<Game> {
+        let json = include_str!("../../tests/fixtures/schedule_2018-06-11.json");
+        let schedule: Schedule = serde_json::from_str(json).unwrap();
+        schedule.dates[0].games.clone()
+    }
+
+    fn row() -> DayRow {
+        DayRow::new(NaiveDate::from_ymd_opt(2018, 6, 11).unwrap())
+    }
+
+    #[test]
+    fn day_without_games_is_empty() {
+        let mut row = row();
+        assert!(row.is_loading());
+        row.set_games(Vec::new());
+        assert!(matches!(row.state, DayState::Empty));
+        assert_eq!(row.selected_idx, None);
+        row.select_next();
+        row.select_prev();
+        assert_eq!(row.selected_idx, None);
+        assert!(row.selected_game().is_none());
+    }
+
+    #[test]
+    fn failed_day_has_no_selection() {
+        let mut row = row();
+        row.set_failed("HTTP 503".to_string());
+        assert!(matches!(&row.state, DayState::Failed(e) if e == "HTTP 503"));
+        row.select_next();
+        assert!(row.selected_game().is_none());
+    }
+
+    #[test]
+    fn selection_wraps_around() {
+        let mut row = row();
+        row.set_games(games());
+        assert_eq!(row.selected_idx, Some(0));
+        row.select_prev();
+        assert_eq!(row.selected_idx, Some(1));
+        row.select_next();
+        assert_eq!(row.selected_idx, Some(0));
+        assert_eq!(row.selected_game().unwrap().gamePk, 530776);
+    }
+
+    #[test]
+    fn finds_the_tile_under_a_point() {
+        let mut row = row();
+        row.set_games(games());
+        row.update(1.0);
+        // Tiles are 200 wide, 260 apart, and the selected one is scaled up by half
+        assert_eq!(row.tile_at(true, [140.0, 0.0]), Some(0));
+        assert_eq!(row.tile_at(false, [140.0, 0.0]), None);
+        assert_eq!(row.tile_at(true, [200.0, 10.0]), Some(1));
+        assert_eq!(row.tile_at(true, [200.0, 100.0]), None);
+        row.select(1);
+        row.update(1.0);
+        assert_eq!(row.selected_idx, Some(1));
+        assert_eq!(row.tile_at(true, [-200.0, 0.0]), Some(0));
+    }
+
+    #[test]
+    fn scrolling_again_starts_from_the_current_position() {
+        let mut row = row();
+        row.set_games(games());
+        row.select_next();
+        row.update(0.05);
+        let halfway = row.scroll_offset();
+        assert!(halfway > 0.5 && halfway < 1.0);
+        // Going back mid-scroll reverses from where the row is, not from the old selection
+        row.select_prev();
+        assert_eq!(row.scroll_offset(), halfway);
+        row.update(1.0);
+        assert_eq!(row.scroll_offset(), 0.0);
+    }
+}
@@ -0,0 +1,293 @@
+use super::mlb_api::{Game, GameTeam};
+use super::{OFFWHITE, WHITE};
+use graphics::character::CharacterCache;
+use graphics::math::Matrix2d;
+use graphics::{rectangle, text, Transformed};
+use opengl_graphics::{GlGraphics, GlyphCache};
+
+const BACKDROP: [f32; 4] = [0.0, 0.0, 0.0, 0.85];
+const SUBTLE: [f32; 4] = [1.0, 1.0, 1.0, 0.7];
+const MARGIN: f64 = 50.0;
+
+// Draws the full-window detail screen for a single game on top of whatever is below it, with
+// `footer` explaining how to leave it
+pub fn render(
+    game: &Game,
+    footer: &str,
+    window_size: [f64; 2],
+    transform: Matrix2d,
+    glyph_cache: &mut GlyphCache,
+    gl: &mut GlGraphics,
+) {
+    let (width, height) = (window_size[0], window_size[1]);
+    let max_width = width - 2.0 * MARGIN;
+    rectangle(BACKDROP, [0.0, 0.0, width, height], transform, gl);
+
+    let recap = game.recap();
+    let away = &game.teams.away;
+    let home = &game.teams.home;
+    let mut y = MARGIN;
+    // Whether any text didn't fit above the bottom margin
+    let mut truncated = false;
+    // Each line advances the cursor by its font size plus some leading
+    let mut line = |s: &str, size: u32, color: [f32; 4], glyph_cache: &mut GlyphCache| {
+        y += size as f64 * 1.4;
+        if y < height - MARGIN {
+            text(color, size, s, glyph_cache, transform.trans(MARGIN, y), gl).unwrap();
+        } else {
+            truncated = truncated || !s.is_empty();
+        }
+    };
+
+    let headline = recap.map_or("No recap available", |r| r.headline.as_str());
+    for l in wrap(headline, 30, max_width, glyph_cache) {
+        line(&l, 30, WHITE, glyph_cache);
+    }
+    if let Some(recap) = recap {
+        for l in wrap(&recap.subhead, 20, max_width, glyph_cache) {
+            line(&l, 20, SUBTLE, glyph_cache);
+        }
+    }
+    line("", 10, WHITE, glyph_cache);
+    let score = match (away.score, home.score) {
+        (Some(away_score), Some(home_score)) => format!(
+            "{}: {} {}, {} {}",
+            game.status.detailedState, away.team.name, away_score, home.team.name, home_score
+        ),
+        _ => format!(
+            "{}: {} @ {}",
+            game.status.detailedState, away.team.name, home.team.name
+        ),
+    };
+    line(&score, 22, WHITE, glyph_cache);
+    if let Some(reason) = &game.status.reason {
+        line(&format!("Reason: {}", reason), 16, SUBTLE, glyph_cache);
+    }
+    line(
+        &format!("{}    {}", team_record(away), team_record(home)),
+        16,
+        SUBTLE,
+        glyph_cache,
+    );
+    line(
+        &format!("Venue: {}", game.venue.name),
+        16,
+        SUBTLE,
+        glyph_cache,
+    );
+    let pitchers: Vec<String> = [
+        ("W", game.winning_pitcher()),
+        ("L", game.losing_pitcher()),
+        ("S", game.saving_pitcher()),
+    ]
+    .iter()
+    .filter_map(|(label, name)| name.map(|name| format!("{}: {}", label, name)))
+    .collect();
+    if !pitchers.is_empty() {
+        line(&pitchers.join("    "), 16, SUBTLE, glyph_cache);
+    }
+    if let Some(recap) = recap {
+        line("", 10, WHITE, glyph_cache);
+        for l in wrap(&recap.blurb, 18, max_width, glyph_cache) {
+            line(&l, 18, WHITE, glyph_cache);
+        }
+        line("", 10, WHITE, glyph_cache);
+        for paragraph in strip_html(&recap.body).lines() {
+            for l in wrap(paragraph, 16, max_width, glyph_cache) {
+                line(&l, 16, WHITE, glyph_cache);
+            }
+        }
+    }
+
+    // The rest of a long recap is cut off; say so in the bottom margin
+    if truncated {
+        let position = transform.trans(MARGIN, height - MARGIN + 16.0);
+        text(SUBTLE, 16, "…", glyph_cache, position, gl).unwrap();
+    }
+
+    text(
+        OFFWHITE,
+        20,
+        footer,
+        glyph_cache,
+        transform.trans(5.0, height - 5.0),
+        gl,
+    )
+    .unwrap();
+}
+
+fn team_record(team: &GameTeam) -> String {
+    format!(
+        "{} {}-{} ({})",
+        team.team.name, team.leagueRecord.wins, team.leagueRecord.losses, team.leagueRecord.pct
+    )
+}
+
+// Greedily break text into lines no wider than max_width
+fn wrap<C>(s: &str, size: u32, max_width: f64, glyph_cache: &mut C) -> Vec<String>
+where
+    C: CharacterCache,
+    C::Error: std::fmt::Debug,
+{
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in s.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if !current.is_empty() && glyph_cache.width(size, &candidate).unwrap() > max_width {
+            lines.push(current);
+            current = word.to_string();
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// Reduce the recap's HTML body to plain text, one paragraph per line
+fn strip_html(html: &str) -> String {
+    let mut out = String::new();
+    let mut chars = html.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // A lone '<' that never closes is text, not the start of a tag
+            '<' if !chars.as_str().contains('>') => out.push('<'),
+            '<' => {
+                let tag: String = chars.by_ref().take_while(|&c| c != '>').collect();
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                if ["p", "br", "div", "li", "h1", "h2", "h3", "h4"].contains(&name.as_str()) {
+                    out.push('\n');
+                }
+            }
+            '&' => {
+                // Only treat short "&...;" runs as entities so a bare ampersand survives
+                let rest = chars.as_str();
+                let entity_end = rest.find(';').filter(|&end| {
+                    end <= 8 && rest[..end].chars().all(|c| c.is_alphanumeric() || c == '#')
+                });
+                match entity_end {
+                    Some(end) => {
+                        out.push_str(&decode_entity(&rest[..end]));
+                        chars = rest[end + 1..].chars();
+                    }
+                    None => out.push('&'),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn decode_entity(entity: &str) -> String {
+    match entity {
+        "amp" => "&".to_string(),
+        "lt" => "<".to_string(),
+        "gt" => ">".to_string(),
+        "quot" => "\"".to_string(),
+        "apos" => "'".to_string(),
+        "nbsp" => " ".to_string(),
+        // Typographic punctuation, common in recaps
+        "lsquo" => "\u{2018}".to_string(),
+        "rsquo" => "\u{2019}".to_string(),
+        "ldquo" => "\u{201c}".to_string(),
+        "rdquo" => "\u{201d}".to_string(),
+        "ndash" => "\u{2013}".to_string(),
+        "mdash" => "\u{2014}".to_string(),
+        "hellip" => "\u{2026}".to_string(),
+        _ => {
+            let code = if entity.starts_with("#x") || entity.starts_with("#X") {
+                u32::from_str_radix(&entity[2..], 16).ok()
+            } else if let Some(decimal) = entity.strip_prefix('#') {
+                decimal.parse().ok()
+            } else {
+                None
+            };
+            // Anything else is left as written rather than dropped
+            code.and_then(std::char::from_u32)
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| format!("&{};", entity))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphics::character::Character;
+    use graphics::ImageSize;
+
+    struct NoTexture;
+
+    impl ImageSize for NoTexture {
+        fn get_size(&self) -> (u32, u32) {
+            (0, 0)
+        }
+    }
+
+    // Every character is one unit wide, whatever the font size
+    struct Monospace(NoTexture);
+
+    impl CharacterCache for Monospace {
+        type Texture = NoTexture;
+        type Error = ();
+
+        fn character(&mut self, _size: u32, _ch: char) -> Result<Character<'_, NoTexture>, ()> {
+            Ok(Character {
+                offset: [0.0; 2],
+                advance_size: [1.0, 0.0],
+                atlas_offset: [0.0; 2],
+                atlas_size: [0.0; 2],
+                texture: &self.0,
+            })
+        }
+    }
+
+    #[test]
+    fn strips_tags_into_paragraphs() {
+        assert_eq!(
+            strip_html("<p>Price <b>dealt</b>.</p><p class=\"x\">Boston won<br/>again</p>"),
+            "\nPrice dealt.\n\nBoston won\nagain\n"
+        );
+        // An unclosed '<' keeps the text after it
+        assert_eq!(strip_html("a < b and c"), "a < b and c");
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            strip_html("Price&rsquo;s &ldquo;best&rdquo; &mdash; 8&nbsp;K&#39;s &#x2026;"),
+            "Price\u{2019}s \u{201c}best\u{201d} \u{2014} 8 K's \u{2026}"
+        );
+        assert_eq!(strip_html("R&amp;D &lt;3"), "R&D <3");
+        // Bare ampersands and entities we don't know survive as written
+        assert_eq!(strip_html("Sox & Yanks"), "Sox & Yanks");
+        assert_eq!(strip_html("&bogus; &#xZZ;"), "&bogus; &#xZZ;");
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let mut cache = Monospace(NoTexture);
+        assert_eq!(
+            wrap("one two three four", 16, 9.0, &mut cache),
+            vec!["one two", "three", "four"]
+        );
+        // A word longer than the line gets a line of its own rather than being split
+        assert_eq!(
+            wrap("a extraordinarily b", 16, 5.0, &mut cache),
+            vec!["a", "extraordinarily", "b"]
+        );
+        assert!(wrap("   ", 16, 5.0, &mut cache).is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+use super::mlb_api::{Direction, Game, MlbApi, MlbError};
+use chrono::NaiveDate;
+use image::RgbaImage;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+// Work handed to the background thread
+enum FetchRequest {
+    Schedule(NaiveDate),
+    GameDate(NaiveDate, Direction),
+}
+
+// Results streamed back to the render thread
+pub enum FetchResult {
+    Schedule {
+        date: NaiveDate,
+        games: Result<Vec<Game>, MlbError>,
+    },
+    Image {
+        game_pk: u32,
+        img: Result<RgbaImage, MlbError>,
+    },
+    GameDate {
+        from: NaiveDate,
+        direction: Direction,
+        found: Result<Option<NaiveDate>, MlbError>,
+    },
+}
+
+struct ImageJob {
+    game_pk: u32,
+    url: String,
+}
+
+// Images waiting for one of the download workers. Workers always take the job whose game comes
+// first in `focus`, so the selected tile and its neighbours load before the rest of the slate.
+#[derive(Default)]
+struct ImageQueue {
+    pending: Vec<ImageJob>,
+    focus: Vec<u32>,
+    in_flight: usize,
+    // Progress through the current batch, which starts when work arrives at an idle queue
+    done: usize,
+    total: usize,
+    closed: bool,
+}
+
+impl ImageQueue {
+    fn is_idle(&self) -> bool {
+        self.pending.is_empty() && self.in_flight == 0
+    }
+
+    fn take_next(&mut self) -> Option<ImageJob> {
+        let focus = &self.focus;
+        let rank = |job: &ImageJob| {
+            focus
+                .iter()
+                .position(|&game_pk| game_pk == job.game_pk)
+                .unwrap_or(usize::MAX)
+        };
+        let idx = (0..self.pending.len()).min_by_key(|&idx| rank(&self.pending[idx]))?;
+        Some(self.pending.remove(idx))
+    }
+
+    // Jobs for games no longer in any row would only hold up the ones on screen, so they are
+    // dropped and no longer count towards the batch
+    fn set_focus(&mut self, focus: Vec<u32>) {
+        let queued = self.pending.len();
+        self.pending.retain(|job| focus.contains(&job.game_pk));
+        self.total -= queued - self.pending.len();
+        self.focus = focus;
+    }
+}
+
+type SharedQueue = Arc<(Mutex<ImageQueue>, Condvar)>;
+
+// Owns the background threads that perform all HTTP and disk access so the render thread never
+// blocks: one for schedule lookups and a pool of `image_concurrency` image downloaders.
+// Textures can only be created on the thread owning the GL context, so images are sent back decoded.
+pub struct Fetcher {
+    requests: Sender<FetchRequest>,
+    results: Receiver<FetchResult>,
+    images: SharedQueue,
+}
+
+impl Fetcher {
+    pub fn spawn(api: MlbApi) -> Self {
+        let (req_tx, req_rx) = channel::<FetchRequest>();
+        let (res_tx, res_rx) = channel::<FetchResult>();
+        let images: SharedQueue = Default::default();
+
+        for _ in 0..api.config().image_concurrency.max(1) {
+            let (api, res_tx, images) = (api.clone(), res_tx.clone(), images.clone());
+            thread::spawn(move || download_images(&api, &images, &res_tx));
+        }
+        thread::spawn(move || {
+            // Exits once the Fetcher (and with it the request sender) has been dropped
+            for request in req_rx {
+                let result = match request {
+                    FetchRequest::Schedule(date) => FetchResult::Schedule {
+                        date,
+                        games: api.get_items(date),
+                    },
+                    FetchRequest::GameDate(from, direction) => FetchResult::GameDate {
+                        from,
+                        direction,
+                        found: api.find_game_date(from, direction),
+                    },
+                };
+                if res_tx.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+        Fetcher {
+            requests: req_tx,
+            results: res_rx,
+            images,
+        }
+    }
+
+    pub fn request_schedule(&self, date: NaiveDate) {
+        self.requests.send(FetchRequest::Schedule(date)).unwrap();
+    }
+
+    // Queue an image download. A queued request for a smaller cut of the same game's image is
+    // superseded by the new one.
+    pub fn request_image(&self, game_pk: u32, url: String) {
+        let (queue, ready) = &*self.images;
+        let mut queue = queue.lock().unwrap();
+        if queue.is_idle() {
+            queue.done = 0;
+            queue.total = 0;
+        }
+        let queued = queue.pending.len();
+        queue.pending.retain(|job| job.game_pk != game_pk);
+        queue.total -= queued - queue.pending.len();
+        queue.pending.push(ImageJob { game_pk, url });
+        queue.total += 1;
+        ready.notify_one();
+    }
+
+    // Games in the order their images should be downloaded. Queued images of games not listed
+    // are cancelled.
+    pub fn set_image_focus(&self, focus: Vec<u32>) {
+        self.images.0.lock().unwrap().set_focus(focus);
+    }
+
+    // (finished, requested) images in the current batch, or None when there is nothing to do
+    pub fn image_progress(&self) -> Option<(usize, usize)> {
+        let queue = self.images.0.lock().unwrap();
+        if queue.is_idle() {
+            None
+        } else {
+            Some((queue.done, queue.total))
+        }
+    }
+
+    pub fn request_game_date(&self, from: NaiveDate, direction: Direction) {
+        self.requests
+            .send(FetchRequest::GameDate(from, direction))
+            .unwrap();
+    }
+
+    // Returns the next finished result without blocking, if any
+    pub fn poll(&self) -> Option<FetchResult> {
+        match self.results.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => panic!("Fetch worker thread terminated"),
+        }
+    }
+}
+
+impl Drop for Fetcher {
+    // Let the download workers finish their current image and exit
+    fn drop(&mut self) {
+        let (queue, ready) = &*self.images;
+        queue.lock().unwrap().closed = true;
+        ready.notify_all();
+    }
+}
+
+// Body of each image download worker
+fn download_images(api: &MlbApi, images: &SharedQueue, results: &Sender<FetchResult>) {
+    let (queue, ready) = &**images;
+    loop {
+        let job = {
+            let mut queue = queue.lock().unwrap();
+            loop {
+                if queue.closed {
+                    return;
+                }
+                if let Some(job) = queue.take_next() {
+                    queue.in_flight += 1;
+                    break job;
+                }
+                queue = ready.wait(queue).unwrap();
+            }
+        };
+        let img = api.get_img(&job.url, job.game_pk);
+        {
+            let mut queue = queue.lock().unwrap();
+            queue.in_flight -= 1;
+            queue.done += 1;
+        }
+        let result = FetchResult::Image {
+            game_pk: job.game_pk,
+            img,
+        };
+        if results.send(result).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(game_pk: u32) -> ImageJob {
+        ImageJob {
+            game_pk,
+            url: format!("https://img.example/{}.jpg", game_pk),
+        }
+    }
+
+    #[test]
+    fn takes_focused_images_first() {
+        let mut queue = ImageQueue {
+            pending: vec![job(1), job(2), job(3), job(4)],
+            focus: vec![3, 2],
+            ..Default::default()
+        };
+        let order: Vec<u32> = std::iter::from_fn(|| queue.take_next())
+            .map(|job| job.game_pk)
+            .collect();
+        // Unfocused games keep the order they were requested in
+        assert_eq!(order, vec![3, 2, 1, 4]);
+    }
+
+    #[test]
+    fn refocusing_drops_images_of_games_out_of_view() {
+        let mut queue = ImageQueue {
+            pending: vec![job(1), job(2), job(3)],
+            done: 2,
+            total: 5,
+            ..Default::default()
+        };
+        queue.set_focus(vec![3, 7]);
+        let pending: Vec<u32> = queue.pending.iter().map(|job| job.game_pk).collect();
+        assert_eq!(pending, vec![3]);
+        assert_eq!((queue.done, queue.total), (2, 3));
+    }
+}
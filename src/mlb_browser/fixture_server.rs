@@ -0,0 +1,146 @@
+use chrono::{Duration, NaiveDate};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+
+// Longest date range answered for a single schedule query
+const MAX_RANGE_DAYS: i64 = 400;
+
+// A tiny HTTP server replaying recorded responses from a directory, so the browser and its tests
+// can run on a machine without internet access. Schedule queries are answered from
+// `schedule_<YYYY-MM-DD>.json` files holding one recorded response per day, stitched together
+// for date ranges; days without a file are treated as off-days. Any other path is served as the
+// file at that path below the directory, which is where recap images go.
+pub struct FixtureServer {
+    addr: SocketAddr,
+}
+
+impl FixtureServer {
+    // Listen on `addr` ("127.0.0.1:0" picks a free port) and serve in the background for the
+    // rest of the process' lifetime
+    pub fn start(dir: PathBuf, addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let dir = dir.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle(&dir, stream) {
+                        eprintln!("Fixture server error ({})", e);
+                    }
+                });
+            }
+        });
+        Ok(FixtureServer { addr })
+    }
+
+    // Root URL to hand to MlbApiConfig::local
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+fn handle(dir: &Path, mut stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers, nothing in them changes the response
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (path, query) = match target.find('?') {
+        Some(i) => (&target[..i], &target[i + 1..]),
+        None => (target, ""),
+    };
+    let response = if path.ends_with("/schedule") {
+        schedule(dir, query).map(|body| ("application/json", body.into_bytes()))
+    } else {
+        file(dir, path).map(|body| (content_type(path), body))
+    };
+
+    let (status, content_type, body) = match response {
+        Some((content_type, body)) => ("200 OK", content_type, body),
+        None => ("404 Not Found", "text/plain", b"Not found".to_vec()),
+    };
+    eprintln!("Fixture server: {} {}", status, target);
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+// Combine the recorded days covered by a `date=` or `startDate=&endDate=` query into one response
+fn schedule(dir: &Path, query: &str) -> Option<String> {
+    let param = |name: &str| {
+        query.split('&').find_map(|pair| {
+            let mut kv = pair.splitn(2, '=');
+            match (kv.next(), kv.next()) {
+                (Some(key), Some(value)) if key == name => Some(value),
+                _ => None,
+            }
+        })
+    };
+    let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+    let start = parse(param("startDate").or_else(|| param("date"))?)?;
+    let end = parse(param("endDate").or_else(|| param("date"))?)?;
+    if end < start || end - start > Duration::days(MAX_RANGE_DAYS) {
+        return None;
+    }
+
+    let mut dates = Vec::new();
+    let mut day = start;
+    while day <= end {
+        let path = dir.join(format!("schedule_{}.json", day.format("%Y-%m-%d")));
+        if let Some(recorded) = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        {
+            if let Some(blocks) = recorded["dates"].as_array() {
+                dates.extend(blocks.iter().cloned());
+            }
+        }
+        day += Duration::days(1);
+    }
+    let total = |key: &str| -> u64 { dates.iter().filter_map(|d| d[key].as_u64()).sum() };
+    let response = json!({
+        "totalItems": total("totalItems"),
+        "totalEvents": total("totalEvents"),
+        "totalGames": total("totalGames"),
+        "totalGamesInProgress": total("totalGamesInProgress"),
+        "dates": dates,
+    });
+    Some(response.to_string())
+}
+
+// Read the file at the request path, refusing anything that would escape the directory
+fn file(dir: &Path, path: &str) -> Option<Vec<u8>> {
+    let relative = Path::new(path.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    fs::read(dir.join(relative)).ok()
+}
+
+fn content_type(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,171 @@
+use super::bindings::Action;
+#[cfg(feature = "gamepad")]
+use super::repeat::KeyRepeat;
+#[cfg(feature = "gamepad")]
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+// Pads are read through gilrs rather than Piston's controller events, which the Glutin window
+// backend never emits. gilrs needs libudev on Linux, so it is only built with the `gamepad`
+// feature; without it Gamepad never reports anything.
+
+// The stick has to be pushed this far to count, and let back to this to stop repeating
+#[cfg(feature = "gamepad")]
+const STICK_PRESS: f32 = 0.5;
+#[cfg(feature = "gamepad")]
+const STICK_RELEASE: f32 = 0.3;
+
+// Reads connected gamepads and turns their buttons, D-pad and left stick into actions. The D-pad
+// and stick navigate like the arrow keys, A and B open and close the details and the shoulder
+// buttons jump a week. A held stick repeats like a held key.
+#[cfg(feature = "gamepad")]
+#[derive(Default)]
+pub struct Gamepad {
+    // None when the platform's gamepad support couldn't be started
+    gilrs: Option<Gilrs>,
+    stick: [f32; 2],
+    // Direction the stick is held in
+    repeat: KeyRepeat,
+}
+
+#[cfg(feature = "gamepad")]
+impl Gamepad {
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(e) => {
+                eprintln!("Gamepads are unavailable: {}", e);
+                None
+            }
+        };
+        Gamepad {
+            gilrs,
+            ..Default::default()
+        }
+    }
+
+    // Actions from the pad events since the last call, followed by repeats of a held stick due
+    // in the last `dt` seconds
+    pub fn update(&mut self, dt: f64) -> Vec<Action> {
+        let mut actions = Vec::new();
+        while let Some(event) = self.gilrs.as_mut().and_then(Gilrs::next_event) {
+            let action = match event.event {
+                EventType::ButtonPressed(button, _) => self.button(button),
+                EventType::AxisChanged(axis, position, _) => self.axis(axis, position),
+                // A pad unplugged mid-push would otherwise keep repeating
+                EventType::Disconnected => {
+                    self.stick = [0.0; 2];
+                    self.repeat.release();
+                    None
+                }
+                _ => None,
+            };
+            actions.extend(action);
+        }
+        actions.extend(self.repeat.update(dt));
+        actions
+    }
+
+    // Diagonals on the D-pad press two buttons, each of which navigates on its own
+    fn button(&self, button: Button) -> Option<Action> {
+        match button {
+            Button::South => Some(Action::OpenDetails),
+            Button::East => Some(Action::Back),
+            Button::LeftTrigger => Some(Action::PrevWeek),
+            Button::RightTrigger => Some(Action::NextWeek),
+            Button::DPadLeft => Some(Action::PrevGame),
+            Button::DPadRight => Some(Action::NextGame),
+            Button::DPadUp => Some(Action::NextDay),
+            Button::DPadDown => Some(Action::PrevDay),
+            _ => None,
+        }
+    }
+
+    // Returns the direction the stick was just pushed in, if any
+    fn axis(&mut self, axis: Axis, position: f32) -> Option<Action> {
+        match axis {
+            Axis::LeftStickX => self.stick[0] = position,
+            Axis::LeftStickY => self.stick[1] = position,
+            _ => return None,
+        }
+        let [x, y] = self.stick;
+        let direction = if x.abs().max(y.abs()) < STICK_PRESS {
+            None
+        } else if x.abs() > y.abs() {
+            Some(if x < 0.0 {
+                Action::PrevGame
+            } else {
+                Action::NextGame
+            })
+        } else {
+            // The Y axis points up
+            Some(if y > 0.0 {
+                Action::NextDay
+            } else {
+                Action::PrevDay
+            })
+        };
+        match (self.repeat.held(), direction) {
+            (Some(held), Some(direction)) if held == direction => None,
+            (_, Some(direction)) => {
+                self.repeat.press(direction);
+                Some(direction)
+            }
+            (Some(_), None) => {
+                if x.abs().max(y.abs()) < STICK_RELEASE {
+                    self.repeat.release();
+                }
+                None
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+// Built without the `gamepad` feature
+#[cfg(not(feature = "gamepad"))]
+pub struct Gamepad;
+
+#[cfg(not(feature = "gamepad"))]
+impl Gamepad {
+    pub fn new() -> Self {
+        Gamepad
+    }
+
+    pub fn update(&mut self, _dt: f64) -> Vec<Action> {
+        Vec::new()
+    }
+}
+
+#[cfg(all(test, feature = "gamepad"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_stick_repeats_after_a_delay() {
+        let mut pad = Gamepad::default();
+        assert_eq!(pad.axis(Axis::LeftStickX, 0.2), None);
+        assert_eq!(pad.axis(Axis::LeftStickX, 0.9), Some(Action::NextGame));
+        // Wobbling within the same direction doesn't count as another push
+        assert_eq!(pad.axis(Axis::LeftStickX, 0.8), None);
+        assert!(pad.update(0.3).is_empty());
+        assert_eq!(pad.update(0.1), vec![Action::NextGame]);
+
+        // Easing off between the thresholds keeps the stick held
+        assert_eq!(pad.axis(Axis::LeftStickX, 0.4), None);
+        assert_eq!(pad.update(0.15).len(), 1);
+        assert_eq!(pad.axis(Axis::LeftStickX, 0.0), None);
+        assert!(pad.update(1.0).is_empty());
+
+        assert_eq!(pad.axis(Axis::LeftStickY, 1.0), Some(Action::NextDay));
+    }
+
+    #[test]
+    fn maps_buttons_and_dpad() {
+        let pad = Gamepad::default();
+        assert_eq!(pad.button(Button::South), Some(Action::OpenDetails));
+        assert_eq!(pad.button(Button::East), Some(Action::Back));
+        assert_eq!(pad.button(Button::RightTrigger), Some(Action::NextWeek));
+        assert_eq!(pad.button(Button::DPadDown), Some(Action::PrevDay));
+        assert_eq!(pad.button(Button::Start), None);
+    }
+}
@@ -0,0 +1,208 @@
+use super::cache::{hash_key, Cache};
+use super::http_client::HttpClient;
+use super::mlb_api::MlbError;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::remove_file;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Stored next to each cached body
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheMeta {
+    url: String,
+    fetched_at: u64,
+    ttl_secs: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CacheMeta {
+    fn is_fresh(&self) -> bool {
+        now() < self.fetched_at.saturating_add(self.ttl_secs)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Fetch `url` as text through the on-disk cache. Fresh entries are returned without touching
+// the network, stale ones are revalidated with ETag/Last-Modified, and `ttl_secs` decides how
+// long a newly downloaded body stays fresh.
+pub fn get_text<F>(
+    http: &HttpClient,
+    cache: &Cache,
+    url: &str,
+    ttl_secs: F,
+) -> Result<String, MlbError>
+where
+    F: Fn(&str) -> u64,
+{
+    let key = hash_key(url);
+    let body_path = cache.path("http", &format!("{}.json", key));
+    let meta_path = cache.path("http", &format!("{}.meta", key));
+    let cached = read_cached(cache, &body_path, &meta_path);
+
+    if let Some((body, meta)) = &cached {
+        if meta.is_fresh() || cache.is_offline() {
+            return Ok(body.clone());
+        }
+    } else if cache.is_offline() {
+        return Err(MlbError::Offline {
+            url: url.to_string(),
+        });
+    }
+
+    let mut headers = HeaderMap::new();
+    if let Some((_, meta)) = &cached {
+        if let Some(etag) = meta.etag.as_ref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = meta.last_modified.as_ref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let response = match http.get(url, headers) {
+        Ok(response) => response,
+        // A stale answer is more useful than none when the network is down
+        Err(e) => match cached {
+            Some((body, _)) => {
+                eprintln!("Serving stale cache for {} ({})", url, e);
+                return Ok(body);
+            }
+            None => return Err(e),
+        },
+    };
+
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED {
+        if let Some((body, mut meta)) = cached {
+            meta.fetched_at = now();
+            meta.ttl_secs = ttl_secs(&body);
+            if let Err(e) = write_meta(cache, &meta_path, &meta) {
+                eprintln!("Unable to cache {} ({})", url, e);
+            }
+            return Ok(body);
+        }
+    }
+    if !status.is_success() {
+        return Err(MlbError::HttpStatus {
+            url: url.to_string(),
+            status,
+        });
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+    let body = response.text()?;
+    let meta = CacheMeta {
+        url: url.to_string(),
+        fetched_at: now(),
+        ttl_secs: ttl_secs(&body),
+        etag,
+        last_modified,
+    };
+    // The metadata is dropped first and written last, so an interrupted write leaves no
+    // usable entry rather than old metadata pointing at a partial body. Failing to cache
+    // doesn't make the response any less good.
+    let _ = remove_file(&meta_path);
+    let written = cache
+        .write(&body_path, body.as_bytes())
+        .and_then(|_| write_meta(cache, &meta_path, &meta));
+    if let Err(e) = written {
+        eprintln!("Unable to cache {} ({})", url, e);
+    }
+    Ok(body)
+}
+
+fn read_cached(cache: &Cache, body_path: &Path, meta_path: &Path) -> Option<(String, CacheMeta)> {
+    let meta = serde_json::from_slice(&cache.read(meta_path)?).ok()?;
+    let body = String::from_utf8(cache.read(body_path)?).ok()?;
+    Some((body, meta))
+}
+
+fn write_meta(cache: &Cache, meta_path: &Path, meta: &CacheMeta) -> Result<(), MlbError> {
+    cache.write(meta_path, serde_json::to_string(meta)?.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::cache::DEFAULT_MAX_BYTES;
+    use super::super::mlb_api::MlbApiConfig;
+    use super::super::test_support::{serve, test_cache, TempDir};
+    use super::*;
+
+    fn client() -> HttpClient {
+        HttpClient::new(&MlbApiConfig {
+            max_retries: 0,
+            ..MlbApiConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn fresh_entries_are_served_without_a_request() {
+        let (_dir, cache) = test_cache("http-fresh");
+        let (url, requests) = serve(vec![("200 OK", "first"), ("200 OK", "second")]);
+        let http = client();
+        assert_eq!(get_text(&http, &cache, &url, |_| 60).unwrap(), "first");
+        assert_eq!(get_text(&http, &cache, &url, |_| 60).unwrap(), "first");
+        assert_eq!(requests.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn stale_entries_are_revalidated() {
+        let (_dir, cache) = test_cache("http-stale");
+        let (url, requests) = serve(vec![
+            ("200 OK\r\nETag: \"v1\"", "first"),
+            ("304 Not Modified", ""),
+            ("200 OK\r\nETag: \"v2\"", "second"),
+        ]);
+        let http = client();
+        assert_eq!(get_text(&http, &cache, &url, |_| 0).unwrap(), "first");
+        assert!(!requests.recv().unwrap().contains("if-none-match"));
+        // Not modified keeps the cached body
+        assert_eq!(get_text(&http, &cache, &url, |_| 0).unwrap(), "first");
+        assert!(requests.recv().unwrap().contains("if-none-match: \"v1\""));
+        assert_eq!(get_text(&http, &cache, &url, |_| 0).unwrap(), "second");
+        assert!(requests.recv().unwrap().contains("if-none-match: \"v1\""));
+    }
+
+    #[test]
+    fn offline_uses_whatever_is_cached() {
+        let (_dir, mut cache) = test_cache("http-offline");
+        let (url, requests) = serve(vec![("200 OK", "first")]);
+        let http = client();
+        assert_eq!(get_text(&http, &cache, &url, |_| 0).unwrap(), "first");
+        cache.set_offline(true);
+        // Stale, but there's nothing better without a network
+        assert_eq!(get_text(&http, &cache, &url, |_| 0).unwrap(), "first");
+        let uncached = format!("{}?date=2018-06-12", url);
+        let result = get_text(&http, &cache, &uncached, |_| 0);
+        assert!(matches!(result, Err(MlbError::Offline { .. })));
+        assert_eq!(requests.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn failing_to_cache_still_returns_the_response() {
+        // A file where the cache directory should be makes every write fail
+        let dir = TempDir::new("http-blocked");
+        let blocker = dir.path().join("cache");
+        std::fs::write(&blocker, "not a directory").unwrap();
+        let cache = Cache::new(blocker, DEFAULT_MAX_BYTES);
+        let (url, _requests) = serve(vec![("200 OK", "first")]);
+        assert_eq!(get_text(&client(), &cache, &url, |_| 60).unwrap(), "first");
+    }
+}
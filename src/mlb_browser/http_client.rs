@@ -0,0 +1,119 @@
+use super::mlb_api::{MlbApiConfig, MlbError};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Never wait longer than this between attempts, whatever the server asks for
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// The one reqwest client shared by schedule and image requests, so connections are reused and
+// every request gets the same timeouts. Overloaded (5xx) and rate limited (429) answers are
+// retried with exponential backoff.
+#[derive(Clone, Debug)]
+pub struct HttpClient {
+    client: Client,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl HttpClient {
+    pub fn new(config: &MlbApiConfig) -> Result<Self, MlbError> {
+        let client = Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.request_timeout)
+            .build()?;
+        Ok(HttpClient {
+            client,
+            max_retries: config.max_retries,
+            backoff: config.backoff,
+        })
+    }
+
+    // GET `url`, returning the final response whatever its status
+    pub fn get(&self, url: &str, headers: HeaderMap) -> Result<Response, MlbError> {
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            let result = self.client.get(url).headers(headers.clone()).send();
+            let elapsed = started.elapsed().as_millis();
+            let response = match result {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("GET {} failed after {} ms ({})", url, elapsed, e);
+                    return Err(e.into());
+                }
+            };
+            let status = response.status();
+            eprintln!("GET {} -> {} in {} ms", url, status, elapsed);
+
+            let retryable = status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+            if !retryable || attempt >= self.max_retries {
+                return Ok(response);
+            }
+            let delay = retry_after(&response)
+                .unwrap_or_else(|| self.backoff * 2u32.saturating_pow(attempt))
+                .min(MAX_BACKOFF);
+            eprintln!(
+                "Retrying {} in {} ms (attempt {} of {})",
+                url,
+                delay.as_millis(),
+                attempt + 2,
+                self.max_retries + 1
+            );
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+// The delay requested by a Retry-After header, when given in seconds
+fn retry_after(response: &Response) -> Option<Duration> {
+    let secs = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    secs.trim().parse().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::serve;
+    use super::*;
+
+    fn client(max_retries: u32) -> HttpClient {
+        HttpClient::new(&MlbApiConfig {
+            max_retries,
+            backoff: Duration::from_millis(1),
+            ..MlbApiConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn retries_server_errors_and_rate_limits() {
+        let (url, _) = serve(vec![
+            ("503 Service Unavailable", ""),
+            ("429 Too Many Requests\r\nRetry-After: 0", ""),
+            ("200 OK", "ok"),
+        ]);
+        let response = client(3).get(&url, HeaderMap::new()).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().unwrap(), "ok");
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let (url, _) = serve(vec![
+            ("500 Internal Server Error", ""),
+            ("502 Bad Gateway", ""),
+        ]);
+        let response = client(1).get(&url, HeaderMap::new()).unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (url, _) = serve(vec![("404 Not Found", ""), ("200 OK", "ok")]);
+        let response = client(3).get(&url, HeaderMap::new()).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,11 +1,105 @@
+use super::cache::{hash_key, Cache};
+use super::http_cache;
+use super::http_client::HttpClient;
+use chrono::{Duration, Local, NaiveDate};
+use image::{DynamicImage, RgbaImage};
+use serde::Deserialize as _;
 use serde_derive::Deserialize;
 use serde_json::Value;
-use std::fs::{create_dir, File};
-use std::io::{copy, Read};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time;
 
-// The following structs are all for deserialization of JSON
-#[allow(non_snake_case)]
+// Everything that can go wrong between the MLB API and a decoded recap image
+#[derive(Debug)]
+pub enum MlbError {
+    // Connection, DNS or body read failure
+    Transport(reqwest::Error),
+    // The server answered, but not with a success status
+    HttpStatus {
+        url: String,
+        status: reqwest::StatusCode,
+    },
+    // The response did not match the schema we deserialize into
+    Json(serde_json::Error),
+    // Reading or writing the on-disk cache failed
+    CacheIo {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // The body ended before the length the server announced
+    Truncated {
+        url: String,
+        expected: u64,
+        received: u64,
+    },
+    // The downloaded bytes are not a decodable image
+    ImageDecode(image::ImageError),
+    // Running with --offline and the response was never cached
+    Offline {
+        url: String,
+    },
+}
+
+impl MlbError {
+    pub fn cache_io(path: &Path, source: io::Error) -> Self {
+        MlbError::CacheIo {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for MlbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MlbError::Transport(e) => write!(f, "Network error: {}", e),
+            MlbError::HttpStatus { url, status } => write!(f, "HTTP {} from {}", status, url),
+            MlbError::Json(e) => write!(f, "Unexpected response format: {}", e),
+            MlbError::CacheIo { path, source } => {
+                write!(f, "Cache error at {}: {}", path.display(), source)
+            }
+            MlbError::Truncated {
+                url,
+                expected,
+                received,
+            } => write!(
+                f,
+                "Incomplete download of {} ({} of {} bytes)",
+                url, received, expected
+            ),
+            MlbError::ImageDecode(e) => write!(f, "Unable to decode image: {}", e),
+            MlbError::Offline { url } => write!(f, "Not available offline: {}", url),
+        }
+    }
+}
+
+impl std::error::Error for MlbError {}
+
+impl From<reqwest::Error> for MlbError {
+    fn from(e: reqwest::Error) -> Self {
+        MlbError::Transport(e)
+    }
+}
+
+impl From<serde_json::Error> for MlbError {
+    fn from(e: serde_json::Error) -> Self {
+        MlbError::Json(e)
+    }
+}
+
+impl From<image::ImageError> for MlbError {
+    fn from(e: image::ImageError) -> Self {
+        MlbError::ImageDecode(e)
+    }
+}
+
+// The following structs are all for deserialization of JSON. They mirror the API response, so
+// not every field is read by the browser.
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct ContentRecapPhotoCutItem {
     pub aspectRatio: String,
@@ -15,68 +109,145 @@ pub struct ContentRecapPhotoCutItem {
     pub at2x: String,
     pub at3x: String,
 }
-#[allow(non_snake_case)]
+// Which sizes are present varies from photo to photo
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct ContentRecapPhotoCuts {
     #[serde(rename = "1920x1080")]
-    pub _1920x1080: ContentRecapPhotoCutItem,
+    pub _1920x1080: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "1440x810")]
-    pub _1440x810: ContentRecapPhotoCutItem,
+    pub _1440x810: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "1280x720")]
-    pub _1280x720: ContentRecapPhotoCutItem,
+    pub _1280x720: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "960x540")]
-    pub _960x540: ContentRecapPhotoCutItem,
+    pub _960x540: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "800x448")]
-    pub _800x448: ContentRecapPhotoCutItem,
+    pub _800x448: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "720x405")]
-    pub _720x405: ContentRecapPhotoCutItem,
+    pub _720x405: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "684x385")]
-    pub _684x385: ContentRecapPhotoCutItem,
+    pub _684x385: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "640x360")]
-    pub _640x360: ContentRecapPhotoCutItem,
+    pub _640x360: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "496x279")]
-    pub _496x279: ContentRecapPhotoCutItem,
+    pub _496x279: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "480x270")]
-    pub _480x270: ContentRecapPhotoCutItem,
+    pub _480x270: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "430x242")]
-    pub _430x242: ContentRecapPhotoCutItem,
+    pub _430x242: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "400x224")]
-    pub _400x224: ContentRecapPhotoCutItem,
+    pub _400x224: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "320x180")]
-    pub _320x180: ContentRecapPhotoCutItem,
+    pub _320x180: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "270x154")]
-    pub _270x154: ContentRecapPhotoCutItem,
+    pub _270x154: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "248x138")]
-    pub _248x138: ContentRecapPhotoCutItem,
+    pub _248x138: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "215x121")]
-    pub _215x121: ContentRecapPhotoCutItem,
+    pub _215x121: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "209x118")]
-    pub _209x118: ContentRecapPhotoCutItem,
+    pub _209x118: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "135x77")]
-    pub _135x77: ContentRecapPhotoCutItem,
+    pub _135x77: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "124x70")]
-    pub _124x70: ContentRecapPhotoCutItem,
+    pub _124x70: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "222x168")]
-    pub _222x168: ContentRecapPhotoCutItem,
+    pub _222x168: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "192x144")]
-    pub _192x144: ContentRecapPhotoCutItem,
+    pub _192x144: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "148x112")]
-    pub _148x112: ContentRecapPhotoCutItem,
+    pub _148x112: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "96x72")]
-    pub _96x72: ContentRecapPhotoCutItem,
+    pub _96x72: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "74x56")]
-    pub _74x56: ContentRecapPhotoCutItem,
+    pub _74x56: Option<ContentRecapPhotoCutItem>,
     #[serde(rename = "1920x810")]
-    pub _1920x810: ContentRecapPhotoCutItem,
+    pub _1920x810: Option<ContentRecapPhotoCutItem>,
+}
+
+impl ContentRecapPhotoCuts {
+    fn all(&self) -> Vec<&ContentRecapPhotoCutItem> {
+        [
+            &self._1920x1080,
+            &self._1440x810,
+            &self._1280x720,
+            &self._960x540,
+            &self._800x448,
+            &self._720x405,
+            &self._684x385,
+            &self._640x360,
+            &self._496x279,
+            &self._480x270,
+            &self._430x242,
+            &self._400x224,
+            &self._320x180,
+            &self._270x154,
+            &self._248x138,
+            &self._215x121,
+            &self._209x118,
+            &self._135x77,
+            &self._124x70,
+            &self._222x168,
+            &self._192x144,
+            &self._148x112,
+            &self._96x72,
+            &self._74x56,
+            &self._1920x810,
+        ]
+        .iter()
+        .filter_map(|cut| cut.as_ref())
+        .collect()
+    }
+
+    // Every rendition with the given aspect ratio, including the @2x and @3x variants
+    pub fn sources(&self, aspect_ratio: &str) -> Vec<ImageSource> {
+        let mut sources = Vec::new();
+        for cut in self.all() {
+            if cut.aspectRatio != aspect_ratio {
+                continue;
+            }
+            for (factor, url) in [(1, &cut.src), (2, &cut.at2x), (3, &cut.at3x)].iter() {
+                if !url.is_empty() {
+                    sources.push(ImageSource {
+                        url: url.to_string(),
+                        width: cut.width * factor,
+                        height: cut.height * factor,
+                    });
+                }
+            }
+        }
+        sources
+    }
+
+    // The smallest rendition at least `min_width` pixels wide, or the largest there is
+    pub fn best_fit(&self, aspect_ratio: &str, min_width: u32) -> Option<ImageSource> {
+        let sources = self.sources(aspect_ratio);
+        let covering = sources
+            .iter()
+            .filter(|s| s.width >= min_width)
+            .min_by_key(|s| s.width);
+        covering
+            .or_else(|| sources.iter().max_by_key(|s| s.width))
+            .cloned()
+    }
+}
+
+// One downloadable size of a recap photo, in pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageSource {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
 }
-#[allow(non_snake_case)]
+
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct ContentRecapPhotos {
     pub title: String,
     pub altText: String,
     pub cuts: ContentRecapPhotoCuts,
 }
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct ContentRecapItems {
     pub r#type: String,
@@ -84,53 +255,192 @@ pub struct ContentRecapItems {
     pub date: String,
     pub id: String,
     pub headline: String,
+    #[serde(default)]
     pub subhead: String,
+    #[serde(default)]
     pub seoTitle: String,
+    #[serde(default)]
     pub seoKeywords: String,
+    #[serde(default)]
     pub seoDescription: String,
+    #[serde(default)]
     pub slug: String,
+    #[serde(default)]
     pub commenting: bool,
-    pub photo: ContentRecapPhotos,
+    pub photo: Option<ContentRecapPhotos>,
+    #[serde(default)]
     pub image: Value,
+    #[serde(default)]
     pub tokenData: Value,
+    #[serde(default)]
     pub blurb: String,
+    #[serde(default)]
     pub body: String,
+    #[serde(default)]
     pub contributor: Value,
+    #[serde(default)]
     pub keywordsDisplay: Value,
+    #[serde(default)]
     pub keywordsAll: Value,
+    #[serde(default)]
     pub approval: String,
+    #[serde(default)]
     pub canonical: String,
+    #[serde(default)]
     pub dataURI: String,
+    #[serde(default)]
     pub primaryKeyword: Value,
+    #[serde(default)]
     pub media: Value,
 }
 
-#[allow(non_snake_case)]
+// Any of the three recaps may be missing, e.g. before one has been written
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct ContentRecap {
-    pub home: ContentRecapItems,
-    pub away: ContentRecapItems,
-    pub mlb: ContentRecapItems,
+    pub home: Option<ContentRecapItems>,
+    pub away: Option<ContentRecapItems>,
+    pub mlb: Option<ContentRecapItems>,
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct ContentEditorial {
-    pub recap: ContentRecap,
+    pub recap: Option<ContentRecap>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContentMediaEpg {
+    pub title: String,
+    #[serde(default)]
+    pub items: Vec<Value>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContentMedia {
+    #[serde(default)]
+    pub epg: Vec<ContentMediaEpg>,
+    #[serde(default)]
+    pub freeGame: bool,
+    #[serde(default)]
+    pub enhancedGame: bool,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct HighlightPlayback {
+    #[serde(default)]
+    pub name: String,
+    pub url: String,
+    pub width: Option<String>,
+    pub height: Option<String>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct HighlightItem {
+    #[serde(default)]
+    pub r#type: String,
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub blurb: String,
+    #[serde(default)]
+    pub description: String,
+    pub duration: Option<String>,
+    #[serde(default)]
+    pub playbacks: Vec<HighlightPlayback>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct HighlightList {
+    #[serde(default)]
+    pub items: Vec<HighlightItem>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContentHighlights {
+    pub highlights: Option<HighlightList>,
+    pub live: Option<HighlightList>,
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct ContentSummary {
+    #[serde(default)]
+    pub hasPreviewArticle: bool,
+    #[serde(default)]
+    pub hasRecapArticle: bool,
+    #[serde(default)]
+    pub hasWrapArticle: bool,
+    #[serde(default)]
+    pub hasHighlightsVideo: bool,
+}
+
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct Content {
     pub link: String,
-    pub editorial: ContentEditorial,
-    pub media: Value,
-    pub highlights: Value,
-    pub summary: Value,
+    pub editorial: Option<ContentEditorial>,
+    // Nice to have, so a section we can't make sense of is dropped rather than the whole game
+    #[serde(default, deserialize_with = "lenient_section")]
+    pub media: Option<ContentMedia>,
+    #[serde(default, deserialize_with = "lenient_section")]
+    pub highlights: Option<ContentHighlights>,
+    #[serde(default, deserialize_with = "lenient_section")]
+    pub summary: Option<ContentSummary>,
+    #[serde(default)]
     pub gameNotes: Value,
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct GameStatus {
+    // "Preview", "Live" or "Final"
+    pub abstractGameState: String,
+    pub codedGameState: String,
+    // e.g. "Scheduled", "In Progress", "Final", "Postponed"
+    pub detailedState: String,
+    pub statusCode: String,
+    pub abstractGameCode: String,
+    // Why a game was postponed or suspended, when it was
+    pub reason: Option<String>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct Person {
+    pub id: u32,
+    pub fullName: String,
+    pub link: String,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct Decisions {
+    pub winner: Option<Person>,
+    pub loser: Option<Person>,
+    // Only present when a save was recorded
+    pub save: Option<Person>,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct Venue {
+    pub id: u32,
+    pub name: String,
+    pub link: String,
+}
+
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct LeagueRecord {
     pub wins: u32,
@@ -138,23 +448,52 @@ pub struct LeagueRecord {
     pub pct: String,
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct TeamInfo {
     pub id: u32,
     pub name: String,
     pub link: String,
+    // Only present when the schedule is hydrated with `team`
+    pub abbreviation: Option<String>,
+}
+
+impl TeamInfo {
+    // "BOS" for the Boston Red Sox, guessed from the name if the API didn't send one
+    pub fn abbreviation(&self) -> String {
+        match &self.abbreviation {
+            Some(abbreviation) => abbreviation.clone(),
+            None => self
+                .name
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .take(3)
+                .collect::<String>()
+                .to_uppercase(),
+        }
+    }
+
+    // Whether `query` names this team: its abbreviation or id, or any part of its name
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        !query.is_empty()
+            && (self.abbreviation().to_lowercase() == query
+                || self.id.to_string() == query
+                || self.name.to_lowercase().contains(&query))
+    }
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct GameTeam {
     pub leagueRecord: LeagueRecord,
-    pub score: u32,
+    // Absent until the game has started
+    pub score: Option<u32>,
     pub team: TeamInfo,
-    pub isWinner: bool,
+    pub isWinner: Option<bool>,
+    #[serde(default)]
     pub splitSquad: bool,
-    pub seriesNumber: u32,
+    pub seriesNumber: Option<u32>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -163,7 +502,7 @@ pub struct GameTeams {
     pub home: GameTeam,
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 #[derive(Deserialize, Debug, Clone)]
 pub struct Game {
     pub gamePk: u32,
@@ -171,84 +510,818 @@ pub struct Game {
     pub gameType: String,
     pub season: String,
     pub gameDate: String,
-    pub status: Value,
+    pub status: GameStatus,
     pub teams: GameTeams,
-    pub decisions: Value,
-    pub venue: Value,
+    // Only present once a game has been decided
+    pub decisions: Option<Decisions>,
+    pub venue: Venue,
     pub content: Content,
-    pub isTie: bool,
+    pub isTie: Option<bool>,
     pub gameNumber: u32,
+    #[serde(default)]
     pub publicFacing: bool,
     pub doubleHeader: String,
+    #[serde(default)]
     pub gamedayType: String,
+    #[serde(default)]
     pub tiebreaker: String,
+    #[serde(default)]
     pub calendarEventID: String,
+    #[serde(default)]
     pub seasonDisplay: String,
+    #[serde(default)]
     pub dayNight: String,
-    pub scheduledInnings: u32,
-    pub inningBreakLength: u32,
-    pub gamesInSeries: u32,
-    pub seriesGameNumber: u32,
+    pub scheduledInnings: Option<u32>,
+    pub inningBreakLength: Option<u32>,
+    pub gamesInSeries: Option<u32>,
+    pub seriesGameNumber: Option<u32>,
+    #[serde(default)]
     pub seriesDescription: String,
+    #[serde(default)]
     pub recordSource: String,
+    #[serde(default)]
     pub ifNecessary: String,
+    #[serde(default)]
     pub ifNecessaryDescription: String,
 }
 
 impl Game {
-    pub fn get_recap(&self) -> (&String, &String) {
-        (
-            &self.content.editorial.recap.mlb.photo.title,
-            &self.content.editorial.recap.mlb.photo.cuts._640x360.src,
-        )
+    // Title and URL of the recap photo, if the game has one
+    pub fn get_recap(&self) -> Option<(&String, &String)> {
+        let photo = self.recap()?.photo.as_ref()?;
+        Some((&photo.title, &photo.cuts._640x360.as_ref()?.src))
     }
 
-    pub fn get_img(url: String, id: String) -> Vec<u8> {
-        // include_bytes!("../assets/cut.jpg")
-        let cache_path = Path::new("./cache");
-        if !cache_path.exists() {
-            create_dir(cache_path).unwrap();
+    // The recap photo size to use for a tile `min_width` pixels wide on screen. Tiles are 16:9,
+    // so the square-ish and panoramic cuts are never picked.
+    pub fn recap_image(&self, min_width: u32) -> Option<ImageSource> {
+        let photo = self.recap()?.photo.as_ref()?;
+        photo.cuts.best_fit("16:9", min_width)
+    }
+
+    pub fn involves_team(&self, query: &str) -> bool {
+        self.teams.away.team.matches(query) || self.teams.home.team.matches(query)
+    }
+
+    // The league-wide recap, which is the one shown in the browser
+    pub fn recap(&self) -> Option<&ContentRecapItems> {
+        self.content
+            .editorial
+            .as_ref()?
+            .recap
+            .as_ref()?
+            .mlb
+            .as_ref()
+    }
+
+    pub fn winning_pitcher(&self) -> Option<&str> {
+        self.decision(|d| &d.winner)
+    }
+
+    pub fn losing_pitcher(&self) -> Option<&str> {
+        self.decision(|d| &d.loser)
+    }
+
+    pub fn saving_pitcher(&self) -> Option<&str> {
+        self.decision(|d| &d.save)
+    }
+
+    fn decision<F>(&self, pick: F) -> Option<&str>
+    where
+        F: Fn(&Decisions) -> &Option<Person>,
+    {
+        pick(self.decisions.as_ref()?)
+            .as_ref()
+            .map(|p| p.fullName.as_str())
+    }
+}
+
+// One block of the `dates` array. A game can appear under more than one date, e.g. a suspended
+// game is listed both on the day it started and the day it is resumed.
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScheduleDate {
+    pub date: String,
+    #[serde(default)]
+    pub totalItems: u32,
+    #[serde(default)]
+    pub totalEvents: u32,
+    #[serde(default)]
+    pub totalGames: u32,
+    #[serde(default)]
+    pub totalGamesInProgress: u32,
+    #[serde(default, deserialize_with = "lenient_games")]
+    pub games: Vec<Game>,
+    // Non-game happenings such as the draft or the All-Star workout day
+    #[serde(default)]
+    pub events: Vec<Value>,
+}
+
+impl ScheduleDate {
+    pub fn naive_date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.date, "%Y-%m-%d").ok()
+    }
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct Schedule {
+    #[serde(default)]
+    pub totalItems: u32,
+    #[serde(default)]
+    pub totalEvents: u32,
+    #[serde(default)]
+    pub totalGames: u32,
+    #[serde(default)]
+    pub totalGamesInProgress: u32,
+    #[serde(default)]
+    pub dates: Vec<ScheduleDate>,
+}
+
+impl Schedule {
+    // Games keyed by date, with blocks repeating a date merged into one list
+    pub fn games_by_date(self) -> BTreeMap<NaiveDate, Vec<Game>> {
+        let mut grouped: BTreeMap<NaiveDate, Vec<Game>> = BTreeMap::new();
+        for block in self.dates {
+            if let Some(date) = block.naive_date() {
+                grouped.entry(date).or_default().extend(block.games);
+            }
         }
-        let fname = cache_path.join(&id);
-        if !fname.is_file() {
-            let mut response = reqwest::blocking::get(&url).unwrap();
-            let mut dest = File::create(&fname).expect("Could not create file");
-            copy(&mut response, &mut dest).unwrap();
+        grouped
+    }
+}
+
+// Games are decoded one at a time so a single entry we can't make sense of is skipped instead
+// of hiding the whole slate
+fn lenient_games<'de, D>(deserializer: D) -> Result<Vec<Game>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let games = Vec::<Value>::deserialize(deserializer)?;
+    Ok(games
+        .iter()
+        .filter_map(|g| match Game::deserialize(g) {
+            Ok(game) => Some(game),
+            Err(e) => {
+                eprintln!("Skipping game {} ({})", g["gamePk"], e);
+                None
+            }
+        })
+        .collect())
+}
+
+// Decodes an optional part of a game, treating one that doesn't match the model as missing
+fn lenient_section<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let section = Value::deserialize(deserializer)?;
+    Ok(T::deserialize(section).ok())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+// Decode JPEG, PNG, WebP or anything else the image crate knows, going by the magic bytes
+// rather than the URL or Content-Type
+fn decode_image(bytes: &[u8]) -> Result<RgbaImage, MlbError> {
+    let format = image::guess_format(bytes)?;
+    let img = match image::load_from_memory_with_format(bytes, format)? {
+        DynamicImage::ImageRgba8(data) => data,
+        x => x.to_rgba(),
+    };
+    Ok(img)
+}
+
+fn parse_schedule(json: &str) -> Result<Schedule, MlbError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+// Cache lifetimes for schedule responses
+const TTL_LIVE_SECS: u64 = 30;
+const TTL_UPCOMING_SECS: u64 = 5 * 60;
+const TTL_FINISHED_SECS: u64 = 30 * 24 * 60 * 60;
+
+// How long a schedule response ending on `end` stays fresh. Days that are over and whose games
+// are all final won't change, while today's slate and anything being played is rechecked often.
+fn schedule_ttl(end: NaiveDate, body: &str) -> u64 {
+    let parsed: serde_json::Value = match serde_json::from_str(body) {
+        Ok(parsed) => parsed,
+        Err(_) => return 0,
+    };
+    let states: Vec<&str> = parsed["dates"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d["games"].as_array())
+        .flatten()
+        .map(|g| g["status"]["abstractGameState"].as_str().unwrap_or(""))
+        .collect();
+    if states.contains(&"Live") {
+        TTL_LIVE_SECS
+    } else if end >= Local::now().date_naive() || states.iter().any(|&s| s != "Final") {
+        TTL_UPCOMING_SECS
+    } else {
+        TTL_FINISHED_SECS
+    }
+}
+
+// Days covered by each schedule range query when searching for games
+const SEARCH_WINDOW_DAYS: i64 = 31;
+// Give up after a full year; the off-season is only about four months long
+const SEARCH_LIMIT_DAYS: i64 = 366;
+
+pub const DEFAULT_BASE_URL: &str = "https://statsapi.mlb.com/api/v1";
+
+// Where requests are sent. Pointing these at a local server (see fixture_server) lets the
+// browser run without internet access.
+#[derive(Clone, Debug)]
+pub struct MlbApiConfig {
+    // Stats API root, without a trailing slash
+    pub base_url: String,
+    // Scheme and host substituted into image URLs from the schedule; None fetches them as-is
+    pub image_host: Option<String>,
+    pub connect_timeout: time::Duration,
+    // Upper bound on a whole request, so a stalled connection can't hold up the fetcher forever
+    pub request_timeout: time::Duration,
+    // Extra attempts after a 5xx or 429 answer, waiting `backoff`, then twice that, and so on
+    pub max_retries: u32,
+    pub backoff: time::Duration,
+    // Number of images downloaded at the same time
+    pub image_concurrency: usize,
+    // Only list games this team plays in (see TeamInfo::matches)
+    pub team: Option<String>,
+}
+
+impl Default for MlbApiConfig {
+    fn default() -> Self {
+        MlbApiConfig {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            image_host: None,
+            connect_timeout: time::Duration::from_secs(5),
+            request_timeout: time::Duration::from_secs(20),
+            max_retries: 3,
+            backoff: time::Duration::from_millis(500),
+            image_concurrency: 4,
+            team: None,
         }
+    }
+}
 
-        let mut buffer = Vec::new();
-        let mut readfile = File::open(&fname).expect("Could not open file");
-        readfile.read_to_end(&mut buffer).unwrap();
-        buffer.to_owned()
+impl MlbApiConfig {
+    // Both the API and the images served from `root`
+    pub fn local(root: &str) -> Self {
+        let root = root.trim_end_matches('/');
+        MlbApiConfig {
+            base_url: format!("{}/api/v1", root),
+            image_host: Some(root.to_string()),
+            ..MlbApiConfig::default()
+        }
+    }
+
+    // Rewrite `url` to the configured image host, keeping its path and query
+    fn image_url(&self, url: &str) -> String {
+        let host = match &self.image_host {
+            Some(host) => host.trim_end_matches('/'),
+            None => return url.to_string(),
+        };
+        let after_scheme = url.find("://").map_or(0, |i| i + 3);
+        match url[after_scheme..].find('/') {
+            Some(i) => format!("{}{}", host, &url[after_scheme + i..]),
+            None => host.to_string(),
+        }
     }
 }
 
-pub struct MlbApi {}
+#[derive(Clone, Debug)]
+pub struct MlbApi {
+    config: MlbApiConfig,
+    cache: Cache,
+    http: HttpClient,
+}
 
 impl MlbApi {
-    pub fn get_items(year: u16, month: u8, day: u8) -> Option<Vec<Game>> {
+    pub fn new(config: MlbApiConfig, cache: Cache) -> Result<Self, MlbError> {
+        let http = HttpClient::new(&config)?;
+        Ok(MlbApi {
+            config,
+            cache,
+            http,
+        })
+    }
+
+    pub fn config(&self) -> &MlbApiConfig {
+        &self.config
+    }
+
+    // Whether a game passes the team filter, if there is one
+    fn is_wanted(&self, game: &Game) -> bool {
+        match &self.config.team {
+            Some(team) => game.involves_team(team),
+            None => true,
+        }
+    }
+
+    // Returns the games scheduled on a date; an off-day yields an empty list
+    pub fn get_items(&self, date: NaiveDate) -> Result<Vec<Game>, MlbError> {
+        let mut schedule = self.get_schedule(date..=date)?;
+        Ok(schedule.remove(&date).unwrap_or_default())
+    }
+
+    // Returns the games for every date in `range` that has any, grouped by date
+    pub fn get_schedule(
+        &self,
+        range: RangeInclusive<NaiveDate>,
+    ) -> Result<BTreeMap<NaiveDate, Vec<Game>>, MlbError> {
         // let json = read_to_string("src/assets/schedule.json").unwrap();
-        let req_url = &format!("http://statsapi.mlb.com/api/v1/schedule?hydrate=game(content(editorial(recap))),decisions&date={}-{:02}-{:02}&sportId=1", year, month, day );
-        if let Ok(json) = reqwest::blocking::get(req_url).unwrap().text() {
-            let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
-            match &parsed["dates"] {
-                serde_json::Value::Array(arr) => {
-                    if !arr.is_empty() {
-                        match serde_json::from_value(arr[0]["games"].to_owned()) {
-                            Ok(v) => Some(v),
-                            Err(e) => {
-                                println!("Unable to parse json found at {} ({})", &req_url, e);
-                                None
-                            }
-                        }
-                    } else {
-                        None
-                    }
+        let (start, end) = (*range.start(), *range.end());
+        let req_url = &format!("{}/schedule?hydrate=game(content(editorial(recap))),decisions,team&startDate={}&endDate={}&sportId=1", self.config.base_url, start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+        let json = http_cache::get_text(&self.http, &self.cache, req_url, |body| {
+            schedule_ttl(end, body)
+        })?;
+        let mut grouped = parse_schedule(&json)?.games_by_date();
+        // Resumed games can be listed under dates outside the requested range
+        grouped.retain(|date, games| {
+            games.retain(|game| self.is_wanted(game));
+            range.contains(date) && !games.is_empty()
+        });
+        Ok(grouped)
+    }
+
+    // Find the nearest date on or after (Forward) / on or before (Backward) `from` that has games.
+    // Queries a month-long startDate/endDate range at a time rather than probing single days.
+    pub fn find_game_date(
+        &self,
+        from: NaiveDate,
+        direction: Direction,
+    ) -> Result<Option<NaiveDate>, MlbError> {
+        let mut searched = 0;
+        while searched < SEARCH_LIMIT_DAYS {
+            let near = Duration::days(searched);
+            let far = Duration::days(searched + SEARCH_WINDOW_DAYS - 1);
+            let window = match direction {
+                Direction::Forward => from
+                    .checked_add_signed(near)
+                    .zip(from.checked_add_signed(far)),
+                Direction::Backward => from
+                    .checked_sub_signed(far)
+                    .zip(from.checked_sub_signed(near)),
+            };
+            // Past the end of the calendar there is nothing left to find
+            let (start, end) = match window {
+                Some(window) => window,
+                None => return Ok(None),
+            };
+            // Team names are only included when hydrated, and only needed to filter by team
+            let hydrate = if self.config.team.is_some() {
+                "&hydrate=team"
+            } else {
+                ""
+            };
+            let req_url = &format!(
+                "{}/schedule?sportId=1&startDate={}&endDate={}{}",
+                self.config.base_url,
+                start.format("%Y-%m-%d"),
+                end.format("%Y-%m-%d"),
+                hydrate
+            );
+            let json = http_cache::get_text(&self.http, &self.cache, req_url, |body| {
+                schedule_ttl(end, body)
+            })?;
+            let schedule = parse_schedule(&json)?;
+            let game_dates = schedule
+                .dates
+                .iter()
+                .filter(|d| match self.config.team {
+                    Some(_) => d.games.iter().any(|game| self.is_wanted(game)),
+                    None => d.totalGames > 0,
+                })
+                .filter_map(ScheduleDate::naive_date);
+            let found = match direction {
+                Direction::Forward => game_dates.min(),
+                Direction::Backward => game_dates.max(),
+            };
+            if found.is_some() {
+                return Ok(found);
+            }
+            searched += SEARCH_WINDOW_DAYS;
+        }
+        Ok(None)
+    }
+
+    // Download (or read from cache) and decode a recap image. Images are keyed by game and URL,
+    // so a replaced recap photo is downloaded again. A cached file that no longer decodes, e.g.
+    // one truncated by a crash, is deleted and fetched afresh.
+    pub fn get_img(&self, url: &str, game_pk: u32) -> Result<RgbaImage, MlbError> {
+        // include_bytes!("../assets/cut.jpg")
+        let fname = self
+            .cache
+            .path("images", &format!("{}-{}", game_pk, hash_key(url)));
+        if let Some(buffer) = self.cache.read(&fname) {
+            match decode_image(&buffer) {
+                Ok(img) => return Ok(img),
+                Err(e) => {
+                    eprintln!(
+                        "Discarding corrupt cached image {} ({})",
+                        fname.display(),
+                        e
+                    );
+                    self.cache.remove(&fname);
                 }
-                _ => None,
             }
-        } else {
-            None
         }
+        let url = self.config.image_url(url);
+        if self.cache.is_offline() {
+            return Err(MlbError::Offline { url });
+        }
+        let response = self.get(&url)?;
+        let expected = response.content_length();
+        let buffer = response.bytes()?.to_vec();
+        if let Some(expected) = expected {
+            if buffer.len() as u64 != expected {
+                return Err(MlbError::Truncated {
+                    url,
+                    expected,
+                    received: buffer.len() as u64,
+                });
+            }
+        }
+        // Only complete images that decode make it into the cache
+        let img = decode_image(&buffer)?;
+        if let Err(e) = self.cache.write(&fname, &buffer) {
+            eprintln!("Unable to cache image for game {} ({})", game_pk, e);
+        }
+        Ok(img)
+    }
+
+    // Issue a GET request, treating any non-success status as an error
+    fn get(&self, url: &str) -> Result<reqwest::blocking::Response, MlbError> {
+        let response = self.http.get(url, Default::default())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(MlbError::HttpStatus {
+                url: url.to_string(),
+                status,
+            });
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixture_server::FixtureServer;
+    use super::super::test_support::{test_cache, TempDir};
+    use super::*;
+
+    const SCHEDULE_2018_06_11: &str = include_str!("../../tests/fixtures/schedule_2018-06-11.json");
+    // Postponed, suspended and recap-less games plus one entry missing its teams
+    const SCHEDULE_IRREGULAR: &str = include_str!("../../tests/fixtures/schedule_irregular.json");
+    // Served by the fixture server: schedule_2018-06-11.json plus its 640x360 recap photos
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    // Three days, with 2018-06-11 split over two blocks and an event-only 2018-06-12
+    const SCHEDULE_RANGE: &str = include_str!("../../tests/fixtures/schedule_range.json");
+
+    fn games() -> Vec<Game> {
+        parse_games(SCHEDULE_2018_06_11)
+    }
+
+    // Video highlights for a game, excluding live clips
+    fn highlights(game: &Game) -> &[HighlightItem] {
+        match game
+            .content
+            .highlights
+            .as_ref()
+            .and_then(|h| h.highlights.as_ref())
+        {
+            Some(list) => &list.items,
+            None => &[],
+        }
+    }
+
+    // Games listed under the first date of a fixture
+    fn parse_games(json: &str) -> Vec<Game> {
+        parse_schedule(json).unwrap().dates.remove(0).games
+    }
+
+    #[test]
+    fn parses_game_status() {
+        let games = games();
+        assert_eq!(games.len(), 2);
+        let status = &games[0].status;
+        assert_eq!(status.abstractGameState, "Final");
+        assert_eq!(status.detailedState, "Final");
+        assert_eq!(status.statusCode, "F");
+        assert!(status.reason.is_none());
+    }
+
+    #[test]
+    fn parses_decisions() {
+        let games = games();
+        let winner = games[0]
+            .decisions
+            .as_ref()
+            .unwrap()
+            .winner
+            .as_ref()
+            .unwrap();
+        assert_eq!(winner.fullName, "David Price");
+        assert_eq!(winner.id, 456034);
+        assert_eq!(games[0].losing_pitcher(), Some("Dylan Bundy"));
+        assert_eq!(games[0].saving_pitcher(), Some("Craig Kimbrel"));
+        // No save was recorded in the second game
+        assert_eq!(games[1].winning_pitcher(), Some("Aaron Nola"));
+        assert_eq!(games[1].saving_pitcher(), None);
+    }
+
+    #[test]
+    fn parses_venue() {
+        let games = games();
+        assert_eq!(games[0].venue.id, 2);
+        assert_eq!(games[0].venue.name, "Oriole Park at Camden Yards");
+        assert_eq!(games[1].venue.name, "Citizens Bank Park");
+    }
+
+    #[test]
+    fn parses_highlights() {
+        let games = games();
+        let first = highlights(&games[0]);
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].title, "Price strikes out 8");
+        assert_eq!(first[0].duration.as_deref(), Some("00:00:42"));
+        let mp4 = first[0]
+            .playbacks
+            .iter()
+            .find(|p| p.name == "mp4Avc")
+            .unwrap();
+        assert!(mp4.url.ends_with(".mp4"));
+        assert_eq!(mp4.width.as_deref(), Some("1280"));
+        assert_eq!(highlights(&games[1]).len(), 1);
+    }
+
+    #[test]
+    fn parses_media_and_summary() {
+        let content = &games()[0].content;
+        let media = content.media.as_ref().unwrap();
+        assert_eq!(media.epg[0].title, "MLBTV");
+        assert!(!media.freeGame);
+        let summary = content.summary.as_ref().unwrap();
+        assert!(summary.hasRecapArticle);
+        assert!(summary.hasHighlightsVideo);
+    }
+
+    #[test]
+    fn tolerates_odd_highlights_media_and_summaries() {
+        let mut schedule: Value = serde_json::from_str(SCHEDULE_2018_06_11).unwrap();
+        let content = &mut schedule["dates"][0]["games"][0]["content"];
+        let highlight = &mut content["highlights"]["highlights"]["items"][0];
+        for field in &["id", "blurb", "description"] {
+            highlight.as_object_mut().unwrap().remove(*field);
+        }
+        content["media"].as_object_mut().unwrap().remove("freeGame");
+        content["summary"] = Value::String("unexpected".to_string());
+        let games = parse_games(&schedule.to_string());
+        assert_eq!(games.len(), 2);
+        let content = &games[0].content;
+        assert_eq!(highlights(&games[0])[0].title, "Price strikes out 8");
+        assert_eq!(highlights(&games[0])[0].blurb, "");
+        assert!(!content.media.as_ref().unwrap().freeGame);
+        assert!(content.summary.is_none());
+    }
+
+    #[test]
+    fn skips_malformed_games() {
+        let games = parse_games(SCHEDULE_IRREGULAR);
+        let pks: Vec<u32> = games.iter().map(|g| g.gamePk).collect();
+        assert_eq!(pks, vec![530800, 530801, 530802, 530776]);
+    }
+
+    #[test]
+    fn parses_postponed_game() {
+        let games = parse_games(SCHEDULE_IRREGULAR);
+        let game = &games[0];
+        assert_eq!(game.status.detailedState, "Postponed");
+        assert_eq!(game.status.reason.as_deref(), Some("Rain"));
+        assert_eq!(game.teams.home.score, None);
+        assert!(game.decisions.is_none());
+        assert_eq!(game.winning_pitcher(), None);
+        assert!(game.recap().is_none());
+        assert!(game.get_recap().is_none());
+        assert!(highlights(game).is_empty());
+    }
+
+    #[test]
+    fn parses_suspended_game() {
+        let games = parse_games(SCHEDULE_IRREGULAR);
+        let game = &games[1];
+        assert_eq!(game.status.detailedState, "Suspended");
+        assert_eq!(game.teams.away.score, Some(2));
+        assert_eq!(game.teams.home.isWinner, None);
+        assert_eq!(game.losing_pitcher(), None);
+        assert!(game.recap().is_none());
+        // Content other than the recap is still available
+        assert_eq!(highlights(game).len(), 2);
+    }
+
+    #[test]
+    fn parses_game_without_recap_photo() {
+        let games = parse_games(SCHEDULE_IRREGULAR);
+        let game = &games[2];
+        let recap = game
+            .content
+            .editorial
+            .as_ref()
+            .unwrap()
+            .recap
+            .as_ref()
+            .unwrap();
+        assert!(recap.mlb.is_none());
+        assert!(recap.home.as_ref().unwrap().photo.is_none());
+        assert!(game.get_recap().is_none());
+        assert_eq!(game.winning_pitcher(), Some("Aaron Nola"));
+    }
+
+    #[test]
+    fn parses_every_date_block() {
+        let schedule = parse_schedule(SCHEDULE_RANGE).unwrap();
+        assert_eq!(schedule.totalGames, 4);
+        assert_eq!(schedule.dates.len(), 4);
+        let draft_day = &schedule.dates[3];
+        assert_eq!(draft_day.totalGames, 0);
+        assert!(draft_day.games.is_empty());
+        assert_eq!(draft_day.events.len(), 1);
+        assert_eq!(draft_day.events[0]["name"], "MLB Draft");
+    }
+
+    #[test]
+    fn groups_games_by_date() {
+        let grouped = parse_schedule(SCHEDULE_RANGE).unwrap().games_by_date();
+        let pks = |y, m, d| -> Vec<u32> {
+            grouped[&NaiveDate::from_ymd_opt(y, m, d).unwrap()]
+                .iter()
+                .map(|g| g.gamePk)
+                .collect()
+        };
+        assert_eq!(pks(2018, 6, 10), vec![530770]);
+        assert_eq!(pks(2018, 6, 11), vec![530776, 530777, 530760]);
+        assert!(pks(2018, 6, 12).is_empty());
+    }
+
+    // An API client talking to a fresh fixture server, with a cache of its own that lives as
+    // long as the returned TempDir
+    fn fixture_api(name: &str) -> (TempDir, FixtureServer, MlbApi) {
+        let server = FixtureServer::start(PathBuf::from(FIXTURES_DIR), "127.0.0.1:0").unwrap();
+        let (dir, cache) = test_cache(name);
+        let api = MlbApi::new(MlbApiConfig::local(&server.url()), cache).unwrap();
+        (dir, server, api)
+    }
+
+    #[test]
+    fn rewrites_image_host() {
+        let url = "https://img.mlbstatic.com/mlb-images/w_640/a.jpg?v=1";
+        assert_eq!(MlbApiConfig::default().image_url(url), url);
+        let config = MlbApiConfig::local("http://127.0.0.1:8080/");
+        assert_eq!(config.base_url, "http://127.0.0.1:8080/api/v1");
+        assert_eq!(
+            config.image_url(url),
+            "http://127.0.0.1:8080/mlb-images/w_640/a.jpg?v=1"
+        );
+    }
+
+    #[test]
+    fn fetches_schedule_from_fixture_server() {
+        let (_dir, _server, api) = fixture_api("api-schedule");
+        let date = NaiveDate::from_ymd_opt(2018, 6, 11).unwrap();
+        let games = api.get_items(date).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].gamePk, 530776);
+        // No recording for the next day, so it is an off-day
+        assert!(api.get_items(date.succ_opt().unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn finds_game_date_on_fixture_server() {
+        let (_dir, _server, api) = fixture_api("api-game-date");
+        let found = Some(NaiveDate::from_ymd_opt(2018, 6, 11).unwrap());
+        let before = NaiveDate::from_ymd_opt(2018, 5, 20).unwrap();
+        let after = NaiveDate::from_ymd_opt(2018, 7, 2).unwrap();
+        assert_eq!(
+            api.find_game_date(before, Direction::Forward).unwrap(),
+            found
+        );
+        assert_eq!(
+            api.find_game_date(after, Direction::Backward).unwrap(),
+            found
+        );
+    }
+
+    #[test]
+    fn stops_searching_at_the_end_of_the_calendar() {
+        let (_dir, _server, api) = fixture_api("api-date-range");
+        assert_eq!(
+            api.find_game_date(NaiveDate::MAX, Direction::Forward)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            api.find_game_date(NaiveDate::MIN, Direction::Backward)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn filters_games_by_team() {
+        let (_dir, _server, mut api) = fixture_api("api-team");
+        let date = NaiveDate::from_ymd_opt(2018, 6, 11).unwrap();
+        let before = NaiveDate::from_ymd_opt(2018, 5, 20).unwrap();
+        api.config.team = Some("phillies".to_string());
+        let games = api.get_items(date).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].gamePk, 530777);
+        assert_eq!(
+            api.find_game_date(before, Direction::Forward).unwrap(),
+            Some(date)
+        );
+
+        api.config.team = Some("NYY".to_string());
+        assert!(api.get_items(date).unwrap().is_empty());
+        assert_eq!(
+            api.find_game_date(before, Direction::Forward).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn fetches_image_from_fixture_server() {
+        let (_dir, _server, api) = fixture_api("api-image");
+        let games = api
+            .get_items(NaiveDate::from_ymd_opt(2018, 6, 11).unwrap())
+            .unwrap();
+        let (_, url) = games[0].get_recap().unwrap();
+        let img = api.get_img(url, games[0].gamePk).unwrap();
+        assert_eq!(img.dimensions(), (640, 360));
+    }
+
+    #[test]
+    fn refetches_corrupt_cached_image() {
+        let (_dir, _server, api) = fixture_api("api-corrupt-image");
+        let games = api
+            .get_items(NaiveDate::from_ymd_opt(2018, 6, 11).unwrap())
+            .unwrap();
+        let (_, url) = games[0].get_recap().unwrap();
+        let fname = api
+            .cache
+            .path("images", &format!("{}-{}", games[0].gamePk, hash_key(url)));
+        // What a crash halfway through writing the file would leave behind
+        let jpeg = include_bytes!("../assets/cut.jpg");
+        api.cache.write(&fname, &jpeg[..jpeg.len() / 2]).unwrap();
+        let img = api.get_img(url, games[0].gamePk).unwrap();
+        assert_eq!(img.dimensions(), (640, 360));
+        assert_eq!(std::fs::read(&fname).unwrap(), jpeg.to_vec());
+    }
+
+    #[test]
+    fn sniffs_image_format() {
+        let mut png = Vec::new();
+        DynamicImage::new_rgba8(4, 3)
+            .write_to(&mut png, image::ImageOutputFormat::PNG)
+            .unwrap();
+        assert_eq!(decode_image(&png).unwrap().dimensions(), (4, 3));
+        assert!(decode_image(b"<html>Not found</html>").is_err());
+    }
+
+    #[test]
+    fn abbreviates_teams() {
+        let games = games();
+        assert_eq!(games[0].teams.away.team.abbreviation(), "BOS");
+        let mut team = games[1].teams.home.team.clone();
+        team.abbreviation = None;
+        assert_eq!(team.abbreviation(), "PHI");
+        assert!(team.matches("phi"));
+        assert!(team.matches(&team.id.to_string()));
+        assert!(team.matches("Phillies"));
+        assert!(!team.matches("Mets"));
+        assert!(!team.matches(" "));
+    }
+
+    #[test]
+    fn picks_smallest_covering_cut() {
+        let game = &games()[0];
+        let pick = |min_width| game.recap_image(min_width).unwrap();
+        assert_eq!(pick(200).width, 209);
+        assert!(pick(200).url.contains("w_209,h_118"));
+        assert_eq!(pick(300).width, 320);
+        // 430x242@3x is a closer fit than 640x360@2x or 1440x810
+        assert_eq!(pick(1281).width, 1290);
+        assert!(pick(1281).url.ends_with("@3x.jpg"));
+        // Nothing is big enough, so take the largest
+        assert_eq!(pick(10000).width, 5760);
+        assert_eq!(pick(10000).height, 3240);
     }
 }
@@ -1,18 +1,42 @@
+pub mod bindings;
+pub mod cache;
+pub mod config;
+mod day_row;
+mod detail;
+pub mod fetcher;
+pub mod fixture_server;
+pub mod gamepad;
+pub mod http_cache;
+pub mod http_client;
 pub mod mlb_api;
+pub mod pointer;
+pub mod repeat;
+#[cfg(test)]
+mod test_support;
+pub mod window_mode;
 // use glutin_window::GlutinWindow as Window;
 use opengl_graphics::{GlGraphics, GlyphCache, ImageSize, Texture, TextureSettings};
 use piston::input::{RenderArgs, UpdateArgs};
 // use piston::input::*;
+use bindings::{Action, Bindings};
+use chrono::{Duration, Months, NaiveDate};
+use day_row::{DayRow, DayState};
+use fetcher::{FetchResult, Fetcher};
 use graphics::math::Matrix2d;
-use image::{DynamicImage, ImageFormat};
-use mlb_api::Game;
-use mlb_api::*;
+use mlb_api::{Direction, Game, MlbApi, MlbError};
+use pointer::{Gesture, SwipeDirection};
 
 struct MenuItem {
     game: Game,
     width: f64,
     height: f64,
-    img_tex: Texture,
+    // None until the background fetcher delivers the recap image
+    img_tex: Option<Texture>,
+    // Pixel width of img_tex, and of the largest cut asked for so far
+    img_width: u32,
+    requested_width: u32,
+    // Set when the image couldn't be loaded, so the generated placeholder is shown for good
+    img_failed: bool,
 }
 
 // Load the font as a binary resource and put it in a static container
@@ -25,20 +49,29 @@ const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
 const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
 const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 const OFFWHITE: [f32; 4] = [1.0, 1.0, 1.0, 0.15];
+const ERROR_RED: [f32; 4] = [1.0, 0.35, 0.35, 1.0];
+// Tint for the tiles of the neighbouring days
+const DIMMED: [f32; 4] = [0.4, 0.4, 0.4, 1.0];
+// Background of the tiles for games without a usable recap image
+const PLACEHOLDER: [f32; 4] = [0.05, 0.12, 0.3, 0.85];
 
 impl MenuItem {
-    pub fn new(game: Game, width: f64, height: f64, img_tex: Texture) -> Self {
+    pub fn new(game: Game, width: f64, height: f64) -> Self {
         MenuItem {
             game,
             width,
             height,
-            img_tex,
+            img_tex: None,
+            img_width: 0,
+            requested_width: 0,
+            img_failed: false,
         }
     }
 
     pub fn render(
         &self,
         is_selected: bool,
+        dimmed: bool,
         transform: Matrix2d,
         scale: f64,
         glyph_cache: &mut GlyphCache,
@@ -49,7 +82,11 @@ impl MenuItem {
             "{} vs {}",
             &self.game.teams.home.team.name, &self.game.teams.away.team.name
         );
-        let (desc_text, _) = self.game.get_recap();
+        // Games without a recap photo (postponed, not yet played) show their status instead
+        let desc_text = match self.game.get_recap() {
+            Some((title, _)) => title,
+            None => &self.game.status.detailedState,
+        };
         let vs_text_font_size = 22;
         let desc_text_font_size = 15;
         let vs_text_width: f64 = vs_text
@@ -73,9 +110,7 @@ impl MenuItem {
 
         let center_x = self.width / 2.0;
         let center_y = self.height / 2.0;
-        let (img_width, img_height) = self.img_tex.get_size();
-        let img_width = img_width as f64;
-        let img_height = img_height as f64;
+        let tint = if dimmed { DIMMED } else { WHITE };
         // Center this block respectively
         if is_selected {
             let scaled_width = scale * self.width;
@@ -88,9 +123,6 @@ impl MenuItem {
                 -desc_text_width / 2.0,
                 center_y * scale + desc_text_font_size as f64,
             );
-            let img_trans = transform
-                .scale(scaled_width / img_width, scaled_height / img_height)
-                .trans(-0.5 * img_width, -0.5 * img_height);
             text(
                 WHITE,
                 vs_text_font_size,
@@ -103,21 +135,84 @@ impl MenuItem {
             text(
                 WHITE,
                 desc_text_font_size,
-                &desc_text,
+                desc_text,
                 glyph_cache,
                 desc_trans,
                 gl,
             )
             .unwrap();
-            graphics::image(&self.img_tex, img_trans, gl);
+            self.render_image(
+                transform.trans(-scaled_width / 2.0, -scaled_height / 2.0),
+                scaled_width,
+                scaled_height,
+                tint,
+                glyph_cache,
+                gl,
+            );
         } else {
             let transform = transform.trans(-center_x, -center_y);
-            let img_trans = transform.scale(self.width / img_width, self.height / img_height);
-            graphics::image(&self.img_tex, img_trans, gl);
+            self.render_image(transform, self.width, self.height, tint, glyph_cache, gl);
+        }
+    }
+
+    // Draws the recap image stretched to the given size, a blank tile while it is loading, or a
+    // generated placeholder with the team abbreviations if there is no image to be had
+    fn render_image(
+        &self,
+        transform: Matrix2d,
+        width: f64,
+        height: f64,
+        tint: [f32; 4],
+        glyph_cache: &mut GlyphCache,
+        gl: &mut GlGraphics,
+    ) {
+        use graphics::{character::CharacterCache, text, DrawState, Image, Transformed};
+        match &self.img_tex {
+            Some(img_tex) => {
+                let (img_width, img_height) = img_tex.get_size();
+                let img_trans =
+                    transform.scale(width / img_width as f64, height / img_height as f64);
+                Image::new_color(tint).draw(img_tex, &DrawState::default(), img_trans, gl);
+            }
+            None if self.img_failed || self.game.recap_image(0).is_none() => {
+                let color = [
+                    PLACEHOLDER[0] * tint[0],
+                    PLACEHOLDER[1] * tint[1],
+                    PLACEHOLDER[2] * tint[2],
+                    PLACEHOLDER[3],
+                ];
+                graphics::rectangle(color, [0.0, 0.0, width, height], transform, gl);
+                let teams = &self.game.teams;
+                let label = format!(
+                    "{} @ {}",
+                    teams.away.team.abbreviation(),
+                    teams.home.team.abbreviation()
+                );
+                let font_size = (height * 0.25) as u32;
+                let label_width: f64 = label
+                    .chars()
+                    .map(|c| glyph_cache.character(font_size, c).unwrap().advance_width())
+                    .sum();
+                let label_trans = transform.trans(
+                    (width - label_width) / 2.0,
+                    (height + font_size as f64 * 0.7) / 2.0,
+                );
+                text(tint, font_size, &label, glyph_cache, label_trans, gl).unwrap();
+            }
+            None => {
+                graphics::rectangle(OFFWHITE, [0.0, 0.0, width, height], transform, gl);
+            }
         }
     }
 }
 
+// A three-quarter ring turned by `rotation`, centred on `transform`
+fn draw_spinner(rotation: f64, radius: f64, transform: Matrix2d, gl: &mut GlGraphics) {
+    let end = rotation + std::f64::consts::PI * 1.5;
+    let bounds = [-radius, -radius, radius * 2.0, radius * 2.0];
+    graphics::circle_arc(WHITE, 2.0, rotation, end, bounds, transform, gl);
+}
+
 pub struct MlbApp {
     gl: GlGraphics, // OpenGL drawing backend.
     rotation: f64,  // Rotation for the square.
@@ -125,11 +220,30 @@ pub struct MlbApp {
     bg_texture: Texture,
     glyph_cache: GlyphCache<'static>,
     bg_size: (f64, f64),
-    items: Option<Vec<MenuItem>>,
-    selected_idx: Option<usize>,
-    prev_selected_idx: Option<usize>,
-    date: (u16, u8, u8),
-    trans_time: f64,
+    // The navigable day in the centre, with the nearest days with games above and below it
+    row: DayRow,
+    next_row: Option<DayRow>,
+    prev_row: Option<DayRow>,
+    // Vertical slide between days: the rows start `day_scroll` rows away and settle at 0
+    day_scroll: f64,
+    day_trans_time: f64,
+    fetcher: Fetcher,
+    // Most recent fetch failure, shown under the date until the next successful load
+    error: Option<String>,
+    // Whether the detail screen for the selected game is covering the carousel
+    showing_details: bool,
+    // Outstanding "nearest day with games" search, as (search start, direction)
+    seek: Option<(NaiveDate, Direction)>,
+    // Pixels per point of the window, so image cuts are picked for the real on-screen size
+    dpi_scale: f64,
+    // Download order last handed to the fetcher
+    image_focus: Vec<u32>,
+    // Size of the last frame in window points, for hit-testing clicks against it
+    window_size: [f64; 2],
+    // Key help shown at the bottom of the carousel and of the detail screen, generated from the
+    // active bindings
+    help: String,
+    back_help: String,
 }
 
 impl MlbApp {
@@ -137,135 +251,387 @@ impl MlbApp {
         gl: GlGraphics, // OpenGL drawing backend.
         bg_texture: Texture,
         bg_size: (f64, f64),
-        date: (u16, u8, u8),
+        date: NaiveDate,
+        api: MlbApi,
+        bindings: &Bindings,
     ) -> Self {
-        // Load JSON data, for now synchronously
-        let games = MlbApi::get_items(date.0, date.1, date.2);
-        MlbApp {
+        // Schedule and images arrive asynchronously and are picked up in update()
+        let mut app = MlbApp {
             gl,
             rotation: 0.0,
             rate: 1.0,
             bg_texture,
             glyph_cache: GlyphCache::from_bytes(FONT, (), TextureSettings::new()).unwrap(),
             bg_size,
-            items: MlbApp::build_menu_items(games),
-            selected_idx: Some(0),
-            prev_selected_idx: Some(0),
-            date,
-            trans_time: 0.0,
-        }
+            row: DayRow::new(date),
+            next_row: None,
+            prev_row: None,
+            day_scroll: 0.0,
+            day_trans_time: 1.0,
+            fetcher: Fetcher::spawn(api),
+            error: None,
+            showing_details: false,
+            seek: None,
+            dpi_scale: 1.0,
+            image_focus: Vec::new(),
+            window_size: [0.0, 0.0],
+            help: bindings.help(),
+            back_help: bindings.back_help(),
+        };
+        app.set_date(date);
+        app
     }
 
+    // Up and Down move to the neighbouring rows, which are already the nearest days with games.
+    // If a neighbour hasn't been found yet, fall back to searching for it.
     pub fn increment_day(&mut self) {
-        self.date.2 += 1;
-        self.rebuild_menu();
+        match self.next_row.take() {
+            Some(next_row) => {
+                let row = std::mem::replace(&mut self.row, next_row);
+                // A day without games (or one that failed to load) isn't the neighbour to go
+                // back to; leaving the slot empty makes prefetch_adjacent look for the real one
+                self.prev_row = Some(row).filter(DayRow::is_loaded);
+                self.start_day_transition(-1.0);
+            }
+            None => self.seek_game_day(Direction::Forward),
+        }
     }
 
     pub fn decrement_day(&mut self) {
-        self.date.2 -= 1;
-        self.rebuild_menu();
+        match self.prev_row.take() {
+            Some(prev_row) => {
+                let row = std::mem::replace(&mut self.row, prev_row);
+                self.next_row = Some(row).filter(DayRow::is_loaded);
+                self.start_day_transition(1.0);
+            }
+            None => self.seek_game_day(Direction::Backward),
+        }
     }
 
-    fn rebuild_menu(&mut self) {
-        if let Some(games) = MlbApi::get_items(self.date.0, self.date.1, self.date.2) {
-            if let Some(items) = MlbApp::build_menu_items(Some(games)) {
-                let len = items.len();
-                self.items = Some(items);
-                if let Some(selected_idx) = self.selected_idx {
-                    if selected_idx >= len {
-                        self.selected_idx = Some(len - 1)
+    fn start_day_transition(&mut self, day_scroll: f64) {
+        self.seek = None;
+        self.day_scroll = day_scroll;
+        self.day_trans_time = 0.0;
+        self.prefetch_adjacent();
+    }
+
+    fn seek_game_day(&mut self, direction: Direction) {
+        // Repeated presses while a search is in flight continue on from that search
+        let base = match self.seek {
+            Some((from, _)) => from,
+            None => self.row.date,
+        };
+        let step = match direction {
+            Direction::Forward => Duration::days(1),
+            Direction::Backward => Duration::days(-1),
+        };
+        if let Some(from) = base.checked_add_signed(step) {
+            self.seek = Some((from, direction));
+            self.fetcher.request_game_date(from, direction);
+        }
+    }
+
+    pub fn increment_week(&mut self) {
+        self.shift_days(7);
+    }
+
+    pub fn decrement_week(&mut self) {
+        self.shift_days(-7);
+    }
+
+    // Month jumps clamp to the last day of shorter months (Mar 31 -> Apr 30)
+    pub fn increment_month(&mut self) {
+        if let Some(date) = self.row.date.checked_add_months(Months::new(1)) {
+            self.set_date(date);
+        }
+    }
+
+    pub fn decrement_month(&mut self) {
+        if let Some(date) = self.row.date.checked_sub_months(Months::new(1)) {
+            self.set_date(date);
+        }
+    }
+
+    fn shift_days(&mut self, days: i64) {
+        if let Some(date) = self.row.date.checked_add_signed(Duration::days(days)) {
+            self.set_date(date);
+        }
+    }
+
+    // Jump to an arbitrary date, discarding the neighbouring rows
+    fn set_date(&mut self, date: NaiveDate) {
+        self.seek = None;
+        self.row = DayRow::new(date);
+        self.next_row = None;
+        self.prev_row = None;
+        self.fetcher.request_schedule(date);
+        self.prefetch_adjacent();
+    }
+
+    // Look up the nearest days with games on either side so Up and Down are instant
+    fn prefetch_adjacent(&mut self) {
+        let date = self.row.date;
+        if self.next_row.is_none() {
+            if let Some(from) = date.succ_opt() {
+                self.fetcher.request_game_date(from, Direction::Forward);
+            }
+        }
+        if self.prev_row.is_none() {
+            if let Some(from) = date.pred_opt() {
+                self.fetcher.request_game_date(from, Direction::Backward);
+            }
+        }
+    }
+
+    fn rows_mut(&mut self) -> impl Iterator<Item = &mut DayRow> {
+        std::iter::once(&mut self.row)
+            .chain(self.next_row.as_mut())
+            .chain(self.prev_row.as_mut())
+    }
+
+    // Drain everything the background fetcher has finished since the last update
+    fn receive_fetched(&mut self) {
+        while let Some(result) = self.fetcher.poll() {
+            match result {
+                FetchResult::Schedule { date, games } => match games {
+                    Ok(games) => {
+                        if date == self.row.date {
+                            self.error = None;
+                        }
+                        // Responses for dates the user has already moved away from match no row.
+                        // Their images are requested by request_images once the games are in.
+                        for row in self.rows_mut() {
+                            if row.date == date && row.is_loading() {
+                                row.set_games(games.clone());
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Unable to load schedule for {} ({})", date, e);
+                        for row in self.rows_mut() {
+                            if row.date == date && row.is_loading() {
+                                row.set_failed(e.to_string());
+                            }
+                        }
+                    }
+                },
+                FetchResult::GameDate {
+                    from,
+                    direction,
+                    found,
+                } => {
+                    if self.seek == Some((from, direction)) {
+                        self.apply_seek(from, found);
+                    } else if let Ok(Some(date)) = found {
+                        self.apply_adjacent(from, direction, date);
                     }
                 }
+                FetchResult::Image { game_pk, img } => match img {
+                    Ok(img) => {
+                        for row in self.rows_mut() {
+                            row.set_image(game_pk, &img);
+                        }
+                    }
+                    // The tile shows a generated placeholder instead
+                    Err(e) => {
+                        eprintln!("Unable to load image for game {} ({})", game_pk, e);
+                        for row in self.rows_mut() {
+                            row.image_failed(game_pk);
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    // Ask for any image whose tile has outgrown what was fetched for it, e.g. newly listed games,
+    // a tile that just became selected or a move to a HiDPI monitor
+    fn request_images(&mut self) {
+        let mut wanted = self.row.wanted_images(true, self.dpi_scale);
+        for row in self.next_row.iter_mut().chain(self.prev_row.iter_mut()) {
+            wanted.extend(row.wanted_images(false, self.dpi_scale));
+        }
+        for (game_pk, url) in wanted {
+            self.fetcher.request_image(game_pk, url);
+        }
+
+        // The selected tile and its neighbours come first, then the days above and below
+        let mut focus = self.row.focus_order();
+        for row in self.next_row.iter().chain(self.prev_row.iter()) {
+            focus.extend(row.focus_order());
+        }
+        if focus != self.image_focus {
+            self.fetcher.set_image_focus(focus.clone());
+            self.image_focus = focus;
+        }
+    }
+
+    // Result of a user-initiated search for the next day with games
+    fn apply_seek(&mut self, from: NaiveDate, found: Result<Option<NaiveDate>, MlbError>) {
+        match found {
+            Ok(Some(date)) => self.set_date(date),
+            Ok(None) => {
+                self.seek = None;
+                self.error = Some(format!("No games found near {}", from));
+            }
+            Err(e) => {
+                self.seek = None;
+                eprintln!("Unable to search for games from {} ({})", from, e);
+                self.error = Some(e.to_string());
             }
         }
     }
 
-    fn build_menu_items(games: Option<Vec<Game>>) -> Option<Vec<MenuItem>> {
-        match games {
-            Some(games_list) => Some(
-                games_list
-                    .iter()
-                    .map(|g| {
-                        let img_tex = {
-                            let (_, url) = g.get_recap();
-                            let img_bytes = Game::get_img(url.clone(), g.gamePk.to_string());
-                            let img = match image::load_from_memory_with_format(
-                                &img_bytes,
-                                ImageFormat::JPEG,
-                            )
-                            .unwrap()
-                            {
-                                DynamicImage::ImageRgba8(data) => data,
-                                x => x.to_rgba(),
-                            };
-                            Texture::from_image(&img, &TextureSettings::new())
-                        };
-                        MenuItem::new(g.to_owned(), 200.0, 200.0 * 9.0 / 16.0, img_tex)
-                    })
-                    .collect(),
-            ),
-            _ => None,
+    // Result of a prefetch search; only used if it still neighbours the current row
+    fn apply_adjacent(&mut self, from: NaiveDate, direction: Direction, date: NaiveDate) {
+        let (expected_from, slot) = match direction {
+            Direction::Forward => (self.row.date.succ_opt(), &mut self.next_row),
+            Direction::Backward => (self.row.date.pred_opt(), &mut self.prev_row),
+        };
+        if slot.is_none() && expected_from == Some(from) {
+            *slot = Some(DayRow::new(date));
+            self.fetcher.request_schedule(date);
         }
     }
 
     pub fn select_next(&mut self) {
-        if let Some(items_list) = &self.items {
-            if let Some(selected) = self.selected_idx {
-                self.prev_selected_idx = self.selected_idx;
-                self.selected_idx = Some((selected + 1) % items_list.len());
-            } else {
-                self.selected_idx = Some(0);
+        self.row.select_next();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.row.select_prev();
+    }
+
+    // Show the detail screen for the selected game, if there is one
+    pub fn open_details(&mut self) {
+        self.showing_details = self.row.selected_game().is_some();
+    }
+
+    // Return to the carousel; the game data is already loaded so nothing is refetched
+    pub fn close_details(&mut self) {
+        self.showing_details = false;
+    }
+
+    pub fn showing_details(&self) -> bool {
+        self.showing_details
+    }
+
+    // Centre of the row `slot` rows below the current one, part way through any day slide
+    fn row_origin(&self, slot: f64) -> [f64; 2] {
+        let (center_x, center_y) = (self.window_size[0] / 2.0, self.window_size[1] / 2.0);
+        // Rows for the neighbouring days sit a third of the window above and below
+        let row_spacing = self.window_size[1] / 3.0;
+        let animated_day_offset = self.day_scroll * (1.0 - self.day_trans_time) * row_spacing;
+        [
+            center_x,
+            center_y * 1.1 + slot * row_spacing + animated_day_offset,
+        ]
+    }
+
+    // Clicking a tile selects it and double-clicking opens its details. A tile on one of the
+    // neighbouring days moves to that day with the tile selected. Swipes drag the carousel:
+    // sideways through the games, up and down through the days.
+    pub fn handle_gesture(&mut self, gesture: Gesture) {
+        if self.showing_details {
+            // Without a keyboard, a tap anywhere is the way back
+            if let Gesture::Tap { .. } = gesture {
+                self.close_details();
             }
+            return;
+        }
+        match gesture {
+            Gesture::Tap { position, double } => self.tap(position, double),
+            Gesture::Swipe(SwipeDirection::Left) => self.select_next(),
+            Gesture::Swipe(SwipeDirection::Right) => self.select_prev(),
+            // Dragging up pulls the earlier day below into the centre
+            Gesture::Swipe(SwipeDirection::Up) => self.decrement_day(),
+            Gesture::Swipe(SwipeDirection::Down) => self.increment_day(),
         }
-        self.trans_time = 0.0;
     }
 
-    pub fn select_prev(&mut self) {
-        if let Some(items_list) = &self.items {
-            if let Some(selected) = self.selected_idx {
-                self.prev_selected_idx = self.selected_idx;
-                self.selected_idx = if selected == 0 {
-                    Some(items_list.len() - 1)
-                } else {
-                    Some(selected - 1 % items_list.len())
-                };
+    // Carry out a key or gamepad action. The detail screen only responds to Back.
+    pub fn perform(&mut self, action: Action) {
+        if self.showing_details {
+            if action == Action::Back {
+                self.close_details();
+            }
+            return;
+        }
+        match action {
+            Action::NextGame => self.select_next(),
+            Action::PrevGame => self.select_prev(),
+            Action::NextDay => self.increment_day(),
+            Action::PrevDay => self.decrement_day(),
+            Action::NextWeek => self.increment_week(),
+            Action::PrevWeek => self.decrement_week(),
+            Action::NextMonth => self.increment_month(),
+            Action::PrevMonth => self.decrement_month(),
+            Action::OpenDetails => self.open_details(),
+            // These act on the window, which belongs to main
+            Action::Back | Action::ToggleFullscreen | Action::Quit => (),
+        }
+    }
+
+    fn tap(&mut self, position: [f64; 2], double: bool) {
+        let hit = |row: &DayRow, slot: f64| {
+            let origin = self.row_origin(slot);
+            row.tile_at(
+                slot == 0.0,
+                [position[0] - origin[0], position[1] - origin[1]],
+            )
+        };
+        let current = hit(&self.row, 0.0);
+        let next = self.next_row.as_ref().and_then(|row| hit(row, -1.0));
+        let prev = self.prev_row.as_ref().and_then(|row| hit(row, 1.0));
+        if let Some(idx) = current {
+            if double && self.row.selected_idx == Some(idx) {
+                self.open_details();
             } else {
-                self.selected_idx = Some(0);
+                self.row.select(idx);
             }
+        } else if let Some(idx) = next {
+            self.increment_day();
+            self.row.select(idx);
+        } else if let Some(idx) = prev {
+            self.decrement_day();
+            self.row.select(idx);
         }
-        self.trans_time = 0.0;
     }
 
     pub fn render(&mut self, args: &RenderArgs) {
+        use graphics::character::CharacterCache;
         use graphics::*;
 
+        if args.window_size[0] > 0.0 {
+            self.dpi_scale = args.draw_size[0] as f64 / args.window_size[0];
+        }
+        self.window_size = args.window_size;
         let bg_texture = &self.bg_texture;
-        let (center_x, center_y) = (args.window_size[0] / 2.0, args.window_size[1] / 2.0);
+        let center_x = args.window_size[0] / 2.0;
         let (bg_w, bg_h) = self.bg_size;
-        let items = &self.items;
-        let selected_idx = {
-            if let Some(selected) = self.selected_idx {
-                selected
-            } else {
-                0
-            }
-        };
-        let prev_selected_idx = {
-            if let Some(prev_selected_idx) = self.prev_selected_idx {
-                prev_selected_idx
-            } else {
-                selected_idx
-            }
-        };
-        let trans_time = self.trans_time;
-        let animated_scroll_offset = prev_selected_idx as f64
-            + (trans_time * (selected_idx as isize - prev_selected_idx as isize) as f64);
+        let row_origins = [-1.0, 0.0, 1.0].map(|slot| self.row_origin(slot));
 
-        let animated_scale = 1.0 + (self.trans_time * 0.5);
-
-        let date_str = &format!("{}-{}-{}", self.date.0, self.date.1, self.date.2);
+        let date_str = &self.row.date.format("%Y-%m-%d").to_string();
+        let error = &self.error;
+        let image_progress = self.fetcher.image_progress();
+        let rotation = self.rotation;
+        let day_state = &self.row.state;
+        let day_image_progress = self.row.image_progress();
         let glyph_cache = &mut self.glyph_cache;
-        let instruction_str = "Use ← → to navigate, ↑ ↓ to change dates, ESC to exit";
+        let instruction_str = &self.help;
+        let back_help = &self.back_help;
+        let detail_game = if self.showing_details {
+            self.row.selected_game()
+        } else {
+            None
+        };
+        // The later day is drawn above, matching Up moving forward in time
+        let rows = [
+            (self.next_row.as_ref(), -1.0, row_origins[0]),
+            (Some(&self.row), 0.0, row_origins[1]),
+            (self.prev_row.as_ref(), 1.0, row_origins[2]),
+        ];
+        let row_y = row_origins[1][1];
 
         self.gl.draw(args.viewport(), |c, gl| {
             // Stretch our background image to the window and draw it
@@ -275,40 +641,101 @@ impl MlbApp {
             graphics::image(bg_texture, bg_trans, gl);
 
             let title_transform = c.transform.trans(50.0, 50.0);
-            text(WHITE, 25, &date_str, glyph_cache, title_transform, gl).unwrap();
+            text(WHITE, 25, date_str, glyph_cache, title_transform, gl).unwrap();
+            // Thin loading bar under the date while images are downloading
+            if let Some((done, total)) = image_progress {
+                let bar_width = 200.0;
+                let filled = bar_width * done as f64 / total.max(1) as f64;
+                let bar_transform = c.transform.trans(50.0, 58.0);
+                rectangle(OFFWHITE, [0.0, 0.0, bar_width, 3.0], bar_transform, gl);
+                rectangle(WHITE, [0.0, 0.0, filled, 3.0], bar_transform, gl);
+            }
+            if let Some(error) = error {
+                let error_transform = c.transform.trans(50.0, 80.0);
+                text(ERROR_RED, 16, error, glyph_cache, error_transform, gl).unwrap();
+            }
             let instruction_transform = c.transform.trans(5.0, args.window_size[1] - 5.0);
-            text(OFFWHITE, 20, &instruction_str, glyph_cache, instruction_transform, gl).unwrap();
-            
-            // For each item in our items list, render it
-            if let Some(items_list) = items {
-                items_list.iter().enumerate().for_each(|(idx, item)| {
-                    // Compute the transform for this item
-                    let transform = c
-                        .transform
-                        .trans(center_x + (idx as f64 * item.width * 1.3), center_y * 1.1)
-                        .trans(animated_scroll_offset * -item.width * 1.3, 0.0);
-                    // Render it
-                    item.render(
-                        selected_idx == idx,
-                        transform,
-                        animated_scale,
-                        glyph_cache,
-                        gl,
-                    );
-                });
+            text(
+                OFFWHITE,
+                20,
+                instruction_str,
+                glyph_cache,
+                instruction_transform,
+                gl,
+            )
+            .unwrap();
+
+            for (row, slot, [x, y]) in rows.iter() {
+                if let Some(row) = row {
+                    let transform = c.transform.trans(*x, *y);
+                    let left = c.transform.trans(50.0, y - 70.0);
+                    row.render(*slot == 0.0, transform, left, glyph_cache, gl);
+                }
+            }
+
+            let centered_text = |s: &str,
+                                 color: [f32; 4],
+                                 size: u32,
+                                 y: f64,
+                                 glyph_cache: &mut GlyphCache,
+                                 gl: &mut GlGraphics| {
+                let width = glyph_cache.width(size, s).unwrap_or(0.0);
+                let transform = c.transform.trans(center_x - width / 2.0, y);
+                text(color, size, s, glyph_cache, transform, gl).unwrap();
+            };
+            // The tiles of a loaded day are drawn by the row; every other state is drawn here
+            match day_state {
+                DayState::Loading => {
+                    draw_spinner(rotation, 24.0, c.transform.trans(center_x, row_y), gl);
+                    centered_text("Loading games", WHITE, 20, row_y + 60.0, glyph_cache, gl);
+                }
+                DayState::Loaded(_) => {
+                    // The day keeps loading until every recap image has arrived
+                    let (done, total) = day_image_progress;
+                    if done < total {
+                        let panel_x = args.window_size[0] - 230.0;
+                        draw_spinner(rotation, 10.0, c.transform.trans(panel_x, 43.0), gl);
+                        let progress = format!("{} of {} images", done, total);
+                        let progress_transform = c.transform.trans(panel_x + 20.0, 50.0);
+                        text(WHITE, 18, &progress, glyph_cache, progress_transform, gl).unwrap();
+                    }
+                }
+                DayState::Empty => {
+                    centered_text("No games scheduled", WHITE, 28, row_y, glyph_cache, gl);
+                }
+                DayState::Failed(e) => {
+                    let message = "Unable to load games";
+                    centered_text(message, ERROR_RED, 28, row_y, glyph_cache, gl);
+                    centered_text(e, ERROR_RED, 16, row_y + 30.0, glyph_cache, gl);
+                }
+            }
+
+            if let Some(game) = detail_game {
+                detail::render(
+                    game,
+                    back_help,
+                    args.window_size,
+                    c.transform,
+                    glyph_cache,
+                    gl,
+                );
             }
         });
     }
 
     pub fn update(&mut self, args: UpdateArgs) {
+        self.receive_fetched();
+        self.request_images();
         // Rotate 2 radians per second.
-        self.rotation += 2.0 * args.dt * self.rate;
-        // Until our transition time scalar reaches 1, increment
-        if self.trans_time < 1.0 {
-            // This means 100%  in 100 ms
-            self.trans_time += args.dt * 10.0;
-            if self.trans_time > 1.0 {
-                self.trans_time = 1.0;
+        self.rotation = (self.rotation + 2.0 * args.dt * self.rate) % std::f64::consts::TAU;
+        for row in self.rows_mut() {
+            row.update(args.dt);
+        }
+        // The day slide takes twice as long as the horizontal scroll
+        if self.day_trans_time < 1.0 {
+            self.day_trans_time += args.dt * 5.0;
+            if self.day_trans_time > 1.0 {
+                self.day_trans_time = 1.0;
             }
         }
     }
@@ -0,0 +1,201 @@
+use piston::input::{Touch, TouchArgs};
+use std::time::{Duration, Instant};
+
+// A press that moves less than this (in window points) is a tap
+const TAP_SLOP: f64 = 10.0;
+// and one that moves at least this far is a swipe. Anything in between is ignored.
+const SWIPE_DISTANCE: f64 = 60.0;
+// Two taps this close together in time and space make a double tap
+const DOUBLE_TAP_TIME: Duration = Duration::from_millis(400);
+const DOUBLE_TAP_SLOP: f64 = 20.0;
+// Touchscreens also send emulated mouse events, which must not count as a second tap
+const TOUCH_MOUSE_SUPPRESS: Duration = Duration::from_millis(500);
+
+// Which way the finger or mouse moved
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Tap { position: [f64; 2], double: bool },
+    Swipe(SwipeDirection),
+}
+
+// Turns mouse and touch input into taps, double taps and swipes, and wheel movement into whole
+// steps. Positions are in window points, the same space the carousel is laid out in.
+#[derive(Default)]
+pub struct Pointer {
+    cursor: [f64; 2],
+    // Where the current press started
+    pressed: Option<[f64; 2]>,
+    last_tap: Option<([f64; 2], Instant)>,
+    last_touch: Option<Instant>,
+    // Wheel movement not yet turned into a step
+    scroll: f64,
+}
+
+impl Pointer {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn move_to(&mut self, position: [f64; 2]) {
+        self.cursor = position;
+    }
+
+    pub fn mouse_press(&mut self, now: Instant) {
+        if !self.recently_touched(now) {
+            self.pressed = Some(self.cursor);
+        }
+    }
+
+    pub fn mouse_release(&mut self, now: Instant) -> Option<Gesture> {
+        if self.recently_touched(now) {
+            return None;
+        }
+        self.release(now)
+    }
+
+    pub fn touch(&mut self, args: &TouchArgs, now: Instant) -> Option<Gesture> {
+        self.last_touch = Some(now);
+        self.cursor = args.position();
+        match args.touch {
+            Touch::Start => {
+                self.pressed = Some(self.cursor);
+                None
+            }
+            Touch::Move => None,
+            Touch::End => self.release(now),
+            Touch::Cancel => {
+                self.pressed = None;
+                None
+            }
+        }
+    }
+
+    // Whole wheel steps in `delta` plus whatever was left over from earlier events. Positive
+    // steps are towards the user (scrolling down).
+    pub fn scroll(&mut self, delta: f64) -> i32 {
+        self.scroll -= delta;
+        let steps = self.scroll.trunc();
+        self.scroll -= steps;
+        steps as i32
+    }
+
+    fn recently_touched(&self, now: Instant) -> bool {
+        self.last_touch
+            .is_some_and(|touched| now.duration_since(touched) < TOUCH_MOUSE_SUPPRESS)
+    }
+
+    fn release(&mut self, now: Instant) -> Option<Gesture> {
+        let start = self.pressed.take()?;
+        let (dx, dy) = (self.cursor[0] - start[0], self.cursor[1] - start[1]);
+        let distance = dx.hypot(dy);
+        if distance >= SWIPE_DISTANCE {
+            self.last_tap = None;
+            let direction = if dx.abs() > dy.abs() {
+                if dx < 0.0 {
+                    SwipeDirection::Left
+                } else {
+                    SwipeDirection::Right
+                }
+            } else if dy < 0.0 {
+                SwipeDirection::Up
+            } else {
+                SwipeDirection::Down
+            };
+            return Some(Gesture::Swipe(direction));
+        }
+        if distance > TAP_SLOP {
+            return None;
+        }
+        let double = self.last_tap.is_some_and(|(position, at)| {
+            now.duration_since(at) < DOUBLE_TAP_TIME
+                && (start[0] - position[0]).hypot(start[1] - position[1]) < DOUBLE_TAP_SLOP
+        });
+        // A third tap starts a new pair rather than making another double
+        self.last_tap = if double { None } else { Some((start, now)) };
+        Some(Gesture::Tap {
+            position: start,
+            double,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click(pointer: &mut Pointer, from: [f64; 2], to: [f64; 2], at: Instant) -> Option<Gesture> {
+        pointer.move_to(from);
+        pointer.mouse_press(at);
+        pointer.move_to(to);
+        pointer.mouse_release(at)
+    }
+
+    #[test]
+    fn recognises_taps_double_taps_and_swipes() {
+        let mut pointer = Pointer::new();
+        let start = Instant::now();
+        let tap = |double| {
+            Some(Gesture::Tap {
+                position: [100.0, 100.0],
+                double,
+            })
+        };
+        assert_eq!(
+            click(&mut pointer, [100.0, 100.0], [103.0, 100.0], start),
+            tap(false)
+        );
+        let soon = start + Duration::from_millis(200);
+        assert_eq!(
+            click(&mut pointer, [100.0, 100.0], [100.0, 100.0], soon),
+            tap(true)
+        );
+        let later = soon + Duration::from_millis(200);
+        assert_eq!(
+            click(&mut pointer, [100.0, 100.0], [100.0, 100.0], later),
+            tap(false)
+        );
+        let much_later = later + DOUBLE_TAP_TIME;
+        assert_eq!(
+            click(&mut pointer, [100.0, 100.0], [100.0, 100.0], much_later),
+            tap(false)
+        );
+
+        let swipe = click(&mut pointer, [300.0, 100.0], [150.0, 130.0], start);
+        assert_eq!(swipe, Some(Gesture::Swipe(SwipeDirection::Left)));
+        let swipe = click(&mut pointer, [300.0, 100.0], [320.0, 300.0], start);
+        assert_eq!(swipe, Some(Gesture::Swipe(SwipeDirection::Down)));
+        // Too far for a tap, not far enough for a swipe
+        assert_eq!(
+            click(&mut pointer, [300.0, 100.0], [330.0, 100.0], start),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_mouse_events_emulated_from_touches() {
+        let mut pointer = Pointer::new();
+        let start = Instant::now();
+        let touch = |touch| TouchArgs::new(0, 1, [50.0, 60.0], 1.0, touch);
+        assert_eq!(pointer.touch(&touch(Touch::Start), start), None);
+        let tap = pointer.touch(&touch(Touch::End), start);
+        assert!(matches!(tap, Some(Gesture::Tap { double: false, .. })));
+        assert_eq!(click(&mut pointer, [50.0, 60.0], [50.0, 60.0], start), None);
+    }
+
+    #[test]
+    fn wheel_movement_accumulates_into_steps() {
+        let mut pointer = Pointer::new();
+        assert_eq!(pointer.scroll(-1.0), 1);
+        assert_eq!(pointer.scroll(0.6), 0);
+        assert_eq!(pointer.scroll(0.6), -1);
+        assert_eq!(pointer.scroll(3.0), -3);
+    }
+}
@@ -0,0 +1,93 @@
+use super::bindings::Action;
+
+// Seconds before a held key starts repeating
+const REPEAT_DELAY: f64 = 0.35;
+// The first repeat interval, shrinking by ACCELERATION each time down to MIN_INTERVAL
+const REPEAT_INTERVAL: f64 = 0.15;
+const ACCELERATION: f64 = 0.85;
+const MIN_INTERVAL: f64 = 0.04;
+
+// Repeats the action of a held key or stick, faster the longer it is held
+#[derive(Default)]
+pub struct KeyRepeat {
+    // The held action, seconds until it next repeats and the interval after that
+    held: Option<(Action, f64, f64)>,
+}
+
+impl KeyRepeat {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // Only moving through games and days repeats; opening details or quitting never should
+    pub fn repeats(action: Action) -> bool {
+        matches!(
+            action,
+            Action::NextGame | Action::PrevGame | Action::NextDay | Action::PrevDay
+        )
+    }
+
+    pub fn held(&self) -> Option<Action> {
+        self.held.map(|(action, _, _)| action)
+    }
+
+    // Start repeating `action`, replacing whatever was held before
+    pub fn press(&mut self, action: Action) {
+        self.held = if KeyRepeat::repeats(action) {
+            Some((action, REPEAT_DELAY, REPEAT_INTERVAL))
+        } else {
+            None
+        };
+    }
+
+    pub fn release(&mut self) {
+        self.held = None;
+    }
+
+    // Repeats due in the last `dt` seconds
+    pub fn update(&mut self, dt: f64) -> Vec<Action> {
+        let mut repeats = Vec::new();
+        if let Some((action, until_repeat, interval)) = &mut self.held {
+            *until_repeat -= dt;
+            while *until_repeat <= 0.0 {
+                repeats.push(*action);
+                *until_repeat += *interval;
+                *interval = (*interval * ACCELERATION).max(MIN_INTERVAL);
+            }
+        }
+        repeats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_keys_repeat_faster_over_time() {
+        let mut repeat = KeyRepeat::new();
+        repeat.press(Action::NextGame);
+        assert!(repeat.update(0.3).is_empty());
+        assert_eq!(repeat.update(0.1), vec![Action::NextGame]);
+        // Each interval is shorter than the last, until they bottom out
+        let mut intervals = Vec::new();
+        let mut since_last = 0.0;
+        for _ in 0..2000 {
+            since_last += 0.001;
+            if !repeat.update(0.001).is_empty() {
+                intervals.push(since_last);
+                since_last = 0.0;
+            }
+        }
+        // The first one was partly used up above
+        let intervals = &intervals[1..];
+        assert!(intervals.windows(2).all(|pair| pair[1] <= pair[0] + 0.0015));
+        assert!(intervals[0] > 0.12);
+        assert!((intervals.last().unwrap() - MIN_INTERVAL).abs() < 0.0015);
+
+        repeat.release();
+        assert!(repeat.update(1.0).is_empty());
+        repeat.press(Action::OpenDetails);
+        assert_eq!(repeat.held(), None);
+    }
+}
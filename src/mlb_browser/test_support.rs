@@ -0,0 +1,81 @@
+// Helpers shared by the tests of several modules
+use super::cache::{Cache, DEFAULT_MAX_BYTES};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+// Keeps directories unique between tests running at the same time
+static DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// A fresh directory under the system temp dir, removed with everything in it when dropped,
+// including when the test panics
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "dss_interview1-test-{}-{}-{}",
+            process::id(),
+            DIR_COUNTER.fetch_add(1, Ordering::Relaxed),
+            name
+        ));
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// An empty cache with the default budget. Keep the TempDir alive for as long as the cache is
+// used.
+pub fn test_cache(name: &str) -> (TempDir, Cache) {
+    let dir = TempDir::new(name);
+    let cache = Cache::new(dir.path().to_path_buf(), DEFAULT_MAX_BYTES);
+    (dir, cache)
+}
+
+// Serve one canned (status and headers, body) response per connection, in order. Returns the
+// server's URL and the head of every request it received, lowercased.
+pub fn serve(responses: Vec<(&'static str, &'static str)>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let (requests, received) = channel();
+    thread::spawn(move || {
+        for (stream, (head, body)) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            // Read up to the blank line ending the head
+            loop {
+                let read = reader.read_line(&mut request).unwrap();
+                if read == 0 || request.ends_with("\r\n\r\n") {
+                    break;
+                }
+            }
+            // Nobody may be listening for requests; that's fine
+            let _ = requests.send(request.to_ascii_lowercase());
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                head,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+    (url, received)
+}
@@ -0,0 +1,156 @@
+use glutin_window::GlutinWindow;
+use piston::window::{AdvancedWindow, Position, Size, Window};
+use std::fs;
+use std::path::PathBuf;
+
+// How the browser occupies the screen. F11 / Alt+Enter cycle through these in order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowMode {
+    Windowed,
+    // An undecorated window covering the whole monitor
+    Borderless,
+    // The platform's own fullscreen mode. winit 0.19 can't change the video mode, so this keeps
+    // the desktop resolution but does take over the monitor.
+    Fullscreen,
+}
+
+impl WindowMode {
+    pub fn next(self) -> Self {
+        match self {
+            WindowMode::Windowed => WindowMode::Borderless,
+            WindowMode::Borderless => WindowMode::Fullscreen,
+            WindowMode::Fullscreen => WindowMode::Windowed,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "windowed",
+            WindowMode::Borderless => "borderless",
+            WindowMode::Fullscreen => "fullscreen",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "windowed" => Some(WindowMode::Windowed),
+            "borderless" => Some(WindowMode::Borderless),
+            "fullscreen" => Some(WindowMode::Fullscreen),
+            _ => None,
+        }
+    }
+
+    // Where the last used mode is remembered between launches
+    fn state_path() -> Option<PathBuf> {
+        Some(
+            dirs::config_dir()?
+                .join("dss_interview1")
+                .join("window_mode"),
+        )
+    }
+
+    // The mode the browser was in when it was last closed
+    pub fn load() -> Option<Self> {
+        WindowMode::from_name(&fs::read_to_string(WindowMode::state_path()?).ok()?)
+    }
+
+    pub fn save(self) {
+        let path = match WindowMode::state_path() {
+            Some(path) => path,
+            None => return,
+        };
+        let saved = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, self.name()));
+        if let Err(e) = saved {
+            eprintln!("Unable to save window mode to {} ({})", path.display(), e);
+        }
+    }
+}
+
+// Switches a window between modes, restoring its windowed size and position on the way back
+pub struct WindowModeSwitcher {
+    mode: WindowMode,
+    windowed: Option<(Size, Option<Position>)>,
+}
+
+impl WindowModeSwitcher {
+    // Assumes the window was built windowed
+    pub fn new() -> Self {
+        WindowModeSwitcher {
+            mode: WindowMode::Windowed,
+            windowed: None,
+        }
+    }
+
+    pub fn toggle(&mut self, window: &mut GlutinWindow) {
+        let mode = self.mode.next();
+        self.set_mode(window, mode);
+        mode.save();
+    }
+
+    pub fn set_mode(&mut self, window: &mut GlutinWindow, mode: WindowMode) {
+        if mode == self.mode {
+            return;
+        }
+        if self.mode == WindowMode::Windowed {
+            self.windowed = Some((window.size(), window.get_position()));
+        }
+        match mode {
+            WindowMode::Windowed => {
+                window.ctx.window().set_fullscreen(None);
+                window.ctx.window().set_decorations(true);
+                if let Some((size, position)) = self.windowed {
+                    window.set_size(size);
+                    if let Some(position) = position {
+                        window.set_position(position);
+                    }
+                }
+            }
+            WindowMode::Borderless => {
+                window.ctx.window().set_fullscreen(None);
+                window.ctx.window().set_decorations(false);
+                // Monitor geometry is in physical pixels, the window in logical ones
+                let monitor = window.ctx.window().get_current_monitor();
+                let hidpi = monitor.get_hidpi_factor();
+                let origin = monitor.get_position();
+                let dimensions = monitor.get_dimensions();
+                window.set_position(Position {
+                    x: (origin.x / hidpi) as i32,
+                    y: (origin.y / hidpi) as i32,
+                });
+                window.set_size(Size {
+                    width: dimensions.width / hidpi,
+                    height: dimensions.height / hidpi,
+                });
+            }
+            WindowMode::Fullscreen => {
+                let monitor = window.ctx.window().get_current_monitor();
+                window.ctx.window().set_decorations(true);
+                window.ctx.window().set_fullscreen(Some(monitor));
+            }
+        }
+        self.mode = mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_through_every_mode_and_round_trips_names() {
+        let mut mode = WindowMode::Windowed;
+        for _ in 0..3 {
+            assert_eq!(WindowMode::from_name(mode.name()), Some(mode));
+            mode = mode.next();
+        }
+        assert_eq!(mode, WindowMode::Windowed);
+        assert_eq!(
+            WindowMode::from_name("fullscreen\n"),
+            Some(WindowMode::Fullscreen)
+        );
+        assert_eq!(WindowMode::from_name("maximized"), None);
+    }
+}